@@ -1,8 +1,14 @@
-use std::cell::RefCell;
-use std::f64::consts::PI;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::{PI, TAU};
 use std::rc::Rc;
+use unicode_bidi::{bidi_class, BidiClass};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
 
 // ============================================================================
 // Configuration Constants
@@ -11,10 +17,94 @@ use wasm_bindgen::JsCast;
 const TEXT_TO_DISPLAY: &str = "[d3-text-sphere2]";
 const ORBIT_RADIUS: f64 = 200.0;
 const ROTATION_SPEED: f64 = 0.3;
+const MAX_ROTATION_SPEED: f64 = 10.0; // rad/s, in either direction
+const DRAG_SENSITIVITY: f64 = 0.01; // Radians of rotation per pixel dragged
+const PINCH_MIN_RADIUS: f64 = 50.0; // Orbit radius limits for pinch zoom
+const PINCH_MAX_RADIUS: f64 = 500.0;
 const LETTER_SIZE: f64 = 48.0;
+const MIN_LETTER_SIZE: f64 = 4.0; // Limits for set_letter_size; smaller is unreadable
+const MAX_LETTER_SIZE: f64 = 500.0;
+const LETTER_SIZE_SPEED: f64 = 100.0; // px/s for animated letter size changes
+const MAX_LETTER_SPACING: f64 = 10.0; // Largest letter spacing factor (0 = stacked)
+const FONT_FAMILY: &str = "Arial, sans-serif";
+const FONT_WEIGHT: &str = "bold";
+const EVENT_NAMES: [&str; 4] = [
+    "reveal_complete",
+    "rotation_lap",
+    "scramble_complete",
+    "warp_complete",
+];
+const WARP_ACCELERATION: f64 = 30.0; // Speed multiplier reached by the end of the warp's first phase
+const CONFIG_STORAGE_KEY: &str = "d3-text-sphere-config"; // localStorage key for saved settings
+const DEFAULT_CONTAINER_ID: &str = "app"; // Element main() creates the first sphere in
+const SNAPSHOT_VERSION: u32 = 1; // Bumped whenever the snapshot format changes
+const RESIZE_DEBOUNCE_MS: u32 = 100; // Quiet period after the last resize event before reflowing
+const IDLE_FLUSH_TIMEOUT_MS: u32 = 100; // Longest deferred updates wait for an idle period
 const SPHERE_RADIUS: f64 = 80.0;
 const PERSPECTIVE_DISTANCE: f64 = 400.0; // Increased for less extreme perspective
 const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
+const MIN_OPACITY: f64 = 0.05; // Opacity of the back-most character when depth fade is on
+const TILT_SPEED: f64 = 1.0; // rad/s for animated inclination changes
+const FEED_RETRY_DELAY: f64 = 1.0; // Seconds before the first text feed reconnect, doubling per attempt
+const MAX_FEED_RETRY_DELAY: f64 = 30.0;
+const CHAR_SOUND_DURATION_MS: f64 = 150.0; // Length of the tone a clicked or highlighted character plays
+const TONE_GAIN: f32 = 0.2; // Starting volume of a tone, which then fades out
+const MIC_FFT_SIZE: u32 = 256; // Analyser FFT size for microphone input (half as many frequency bins)
+const MIC_MIN_SPEED_FACTOR: f64 = 0.2; // Rotation speed multiplier at silence
+const MIC_MAX_SPEED_FACTOR: f64 = 3.0; // Rotation speed multiplier at the analyser's loudest level
+const BAND_AMPLITUDE_FACTOR: f64 = 2.0; // Size gain of a character whose frequency band is at full level
+const BLUR_SPEED_FACTOR: f64 = 0.1; // Fraction of its speed the orbit keeps while the window is blurred
+const BLUR_SPIN_DURATION: f64 = 1.0; // Seconds to slow down on blur and speed back up on focus
+const BEAT_PULSE_AMPLITUDE: f64 = 0.3; // Size boost on each beat when set_beat_pulse starts the pulse
+const BEAT_DECAY_RATE: f64 = 5.0; // Decay of the beat envelope per beat (e^-5 is ~1% left at the next beat)
+const TAP_TEMPO_TAPS: usize = 8; // Taps averaged by tap_tempo
+const TAP_RESET_MS: f64 = 2000.0; // A gap longer than this starts a new tap sequence
+const SYNC_INTERVAL_MS: f64 = 100.0; // Tab sync leader broadcasts its angle this often
+const SYNC_ELECTION_MS: i32 = 300; // A tab claiming leadership takes it if nobody objects in time
+const SYNC_LEADER_TIMEOUT_MS: f64 = 1000.0; // Followers re-elect after this long without an angle
+const FETCH_TIMEOUT_MS: i32 = 5000; // fetch_text gives up after this long
+const MAX_CLIPBOARD_CHARS: usize = 100; // Pasted text is cut to this many characters
+const LOADING_OPACITY: f64 = 0.3; // Character opacity while fetch_text is in flight
+const TICKER_TRAVEL: f64 = 1.5 * PI; // Orbit angle a ticker character covers before sliding off
+const TICKER_SLOT: f64 = PI / 12.0; // Orbit angle between adjacent ticker characters
+const TICKER_HUE_STEP: f64 = 15.0; // Degrees of hue between successive ticker characters
+const SPEED_ACCELERATION: f64 = 1.0; // rad/s² for smooth rotation speed changes
+const SPRING_EASE_PERIOD: f64 = 0.3; // Oscillation length of the spring easing (fraction of a transition)
+const RADIUS_SPEED: f64 = 200.0; // px/s for animated orbit and sphere radius changes
+const MIN_ORBIT_RADIUS: f64 = 10.0; // Limits for animated orbit radius changes
+const MAX_ORBIT_RADIUS: f64 = 1000.0;
+const WRAP_RING_TILT: f64 = 30.0; // Degrees between rings stacked by wrap_and_display
+const DEPTH_COLD_HUE: f64 = 210.0; // Hue of the back-most character with depth coloring
+const DEPTH_WARM_HUE: f64 = 30.0; // Hue of the front-most character with depth coloring
+const MAX_GLOW: f64 = 4.0; // Blur std deviation (px) of the front-most character's glow
+const SHADOW_DARKNESS: f64 = 0.25; // Brightness of a character at the center of the sphere's shadow
+const LIGHT_DIRECTION: (f64, f64, f64) = (0.0, 1.0, 0.5); // Towards the light: above and in front
+const LIGHT_ORBIT_RADIUS: f64 = 300.0; // px from the vertical axis of an orbiting light
+const LIGHT_ORBIT_HEIGHT: f64 = 150.0; // px above the center of an orbiting light
+const RIM_THRESHOLD: f64 = 0.6; // Alignment with the back light at which rim lighting starts
+const RIM_STRENGTH: f64 = 0.4; // Brightness added by rim lighting at full alignment
+const SHADOW_SOFTNESS: f64 = 20.0; // Width (px) of the shadow's penumbra
+const FPS_WINDOW: usize = 60; // Frames averaged by get_fps()
+const QUALITY_LOW_FPS: f64 = 30.0; // Adaptive quality steps down below this FPS
+const QUALITY_HIGH_FPS: f64 = 50.0; // ...and back up above this FPS
+const QUALITY_STEP_INTERVAL: f64 = 2.0; // Minimum seconds between quality changes
+const CURSOR_STRENGTH: f64 = 100_000.0; // Cursor force = strength / distance²
+const INFLUENCE_RADIUS: f64 = 150.0; // Pixels beyond which the cursor has no effect
+const MAX_DISPLACEMENT: f64 = 40.0; // Furthest (px) the cursor can move a character
+const SPRING_STIFFNESS: f64 = 120.0; // Spring constant pulling displaced characters home
+const SPRING_DAMPING: f64 = 8.0; // Below 2√k the return overshoots and oscillates
+const HIGHLIGHT_SCALE: f64 = 1.3; // Size multiplier of a highlighted character
+const HIGHLIGHT_COLOR: &str = "#FFD700";
+const DROP_TARGET_GRADIENT: [&str; 3] = ["#FFF3B0", "#FFD700", "#B8860B"]; // Sphere while text is dragged over it
+const HIGH_CONTRAST_FOREGROUND: &str = "#FFFFFF"; // Characters and sphere in high-contrast mode
+const HIGH_CONTRAST_BACKGROUND: &str = "#000000"; // Background and sphere outline
+const HIGH_CONTRAST_OUTLINE: f64 = 3.0; // Sphere outline width (px)
+const MAX_SPRING_STEP: f64 = 0.05; // Longest Euler step (s) before the spring goes unstable
+const MAX_SPRING_PHASE_STEP: f64 = 0.5; // Largest ω·dt per randomize spring sub-step (diverges past ~2)
+const KEY_NUDGE_ANGLE: f64 = 0.1; // Radians per left/right arrow press
+const KEY_SPEED_STEP: f64 = 0.05; // rad/s per up/down arrow press
+const GYRO_SMOOTHING: f64 = 0.1; // Weight of each new device orientation reading (low-pass filter)
+const GYRO_REST_BETA: f64 = 45.0; // Front-back tilt (degrees) of a phone held normally = flat orbit
 
 // ============================================================================
 // d3.js JavaScript Bindings
@@ -30,12 +120,12 @@ const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
             .append('svg')
             .attr('width', width)
             .attr('height', height)
-            .attr('id', 'main-svg');
+            .attr('id', container_id + '-svg');
 
         // Add gradient for sphere
         const defs = svg.append('defs');
         const gradient = defs.append('radialGradient')
-            .attr('id', 'sphere-gradient')
+            .attr('id', container_id + '-sphere-gradient')
             .attr('cx', '35%')
             .attr('cy', '35%')
             .attr('r', '60%');
@@ -53,19 +143,20 @@ const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
         return svg.node();
     }
 
-    export function update_svg_size(width, height) {
-        d3.select('#main-svg')
+    export function update_svg_size(svg, width, height) {
+        d3.select(svg)
             .attr('width', width)
             .attr('height', height);
     }
 
     export function create_sphere(svg, cx, cy, radius) {
+        const gradient = d3.select(svg).select('radialGradient').attr('id');
         return d3.select(svg)
             .append('circle')
             .attr('cx', cx)
             .attr('cy', cy)
             .attr('r', radius)
-            .attr('fill', 'url(#sphere-gradient)')
+            .attr('fill', `url(#${gradient})`)
             .node();
     }
 
@@ -75,6 +166,86 @@ const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
             .attr('cy', cy);
     }
 
+    export function update_sphere_radius(sphere, radius) {
+        d3.select(sphere).attr('r', radius);
+    }
+
+    export function update_background(svg, fill) {
+        const root = d3.select(svg);
+        let rect = root.select('#background-rect');
+        if (rect.empty()) {
+            // First child, so it stays behind everything else
+            rect = root.insert('rect', ':first-child')
+                .attr('id', 'background-rect')
+                .attr('x', 0)
+                .attr('y', 0)
+                .attr('width', '100%')
+                .attr('height', '100%');
+        }
+        rect.attr('fill', fill);
+    }
+
+    export function update_background_gradient(svg, top, bottom) {
+        const defs = d3.select(svg).select('defs');
+        const id = svg.id + '-background-gradient';
+        let gradient = defs.select('#' + id);
+        if (gradient.empty()) {
+            gradient = defs.append('linearGradient')
+                .attr('id', id)
+                .attr('x1', 0)
+                .attr('y1', 0)
+                .attr('x2', 0)
+                .attr('y2', 1);
+            gradient.append('stop').attr('offset', '0%');
+            gradient.append('stop').attr('offset', '100%');
+        }
+        const colors = [top, bottom];
+        gradient.selectAll('stop').attr('stop-color', (_, i) => colors[i]);
+        update_background(svg, `url(#${id})`);
+    }
+
+    export function remove_background(svg) {
+        d3.select(svg).select('#background-rect').remove();
+        d3.select(svg).select('#' + svg.id + '-background-gradient').remove();
+    }
+
+    export function save_to_local_storage(key, value) {
+        try {
+            window.localStorage.setItem(key, value);
+        } catch (e) {
+            console.warn('could not save settings:', e);
+        }
+    }
+
+    export function load_from_local_storage(key) {
+        try {
+            return window.localStorage.getItem(key);
+        } catch (e) {
+            return null;
+        }
+    }
+
+    export function remove_from_local_storage(key) {
+        try {
+            window.localStorage.removeItem(key);
+        } catch (e) {
+            console.warn('could not clear settings:', e);
+        }
+    }
+
+    export function update_sphere_gradient(svg, highlight, mid, shadow) {
+        const colors = [highlight, mid, shadow];
+        d3.select(svg).select('radialGradient')
+            .selectAll('stop')
+            .attr('stop-color', (_, i) => colors[i]);
+    }
+
+    export function update_sphere_outline(svg, color, width) {
+        d3.select(svg).select('circle')
+            .attr('stroke', width > 0 ? color : null)
+            .attr('stroke-width', width > 0 ? width : null);
+    }
+
     export function create_text_element(svg, x, y, char, fill, font_size, skew_x) {
         return d3.select(svg)
             .append('text')
@@ -103,6 +274,111 @@ const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
             .attr('transform', `translate(${x}, ${y}) scale(${scale_x}, 1) skewX(${skew_x}) translate(${-x}, ${-y})`);
     }
 
+    export function create_glow_filter(svg, id, blur_std_dev) {
+        // Glow = sharp source composited over a blurred copy of itself
+        const filter = d3.select(svg).select('defs')
+            .append('filter')
+            .attr('id', id)
+            .attr('x', '-50%')
+            .attr('y', '-50%')
+            .attr('width', '200%')
+            .attr('height', '200%');
+
+        filter.append('feGaussianBlur')
+            .attr('in', 'SourceGraphic')
+            .attr('stdDeviation', blur_std_dev)
+            .attr('result', 'blur');
+        filter.append('feComposite')
+            .attr('in', 'SourceGraphic')
+            .attr('in2', 'blur')
+            .attr('operator', 'over');
+
+        return filter.node();
+    }
+
+    export function update_glow_filter(filter, blur_std_dev) {
+        d3.select(filter).select('feGaussianBlur')
+            .attr('stdDeviation', blur_std_dev);
+    }
+
+    export function set_element_filter(element, id) {
+        if (id) {
+            d3.select(element).attr('filter', `url(#${id})`);
+        } else {
+            d3.select(element).attr('filter', null);
+        }
+        const shadow = element.dataset && element.dataset.shadow;
+        if (shadow) {
+            element.style.filter = id ? `url(#${id}) ${shadow}` : shadow;
+        }
+    }
+
+    export function update_text_color(element, fill) {
+        d3.select(element).attr('fill', fill);
+    }
+
+    export function update_text_font(element, family) {
+        d3.select(element).attr('font-family', family);
+    }
+
+    export function update_text_stroke(element, width, color, opacity) {
+        const el = d3.select(element);
+        if (width > 0) {
+            // Draw the stroke first so it doesn't eat into the fill
+            el.attr('stroke', color)
+                .attr('stroke-width', width)
+                .attr('stroke-opacity', opacity)
+                .attr('paint-order', 'stroke fill');
+        } else {
+            el.attr('stroke', null)
+                .attr('stroke-width', null)
+                .attr('stroke-opacity', null)
+                .attr('paint-order', null);
+        }
+    }
+
+    // The shadow is a CSS filter, which overrides the glow's filter
+    // attribute, so both are kept in the style while either is set
+    export function update_text_shadow(element, dx, dy, blur, color) {
+        if (dx === 0 && dy === 0 && blur === 0) {
+            delete element.dataset.shadow;
+            element.style.filter = '';
+            return;
+        }
+        const shadow = `drop-shadow(${dx}px ${dy}px ${blur}px ${color})`;
+        element.dataset.shadow = shadow;
+        const glow = element.getAttribute('filter');
+        element.style.filter = glow ? `${glow} ${shadow}` : shadow;
+    }
+
+    export function update_text_weight(element, weight) {
+        d3.select(element).attr('font-weight', weight);
+    }
+
+    export function update_text_highlight(element, size_multiplier, fill) {
+        // Scales the font size just set by update_text_element
+        const el = d3.select(element);
+        const size = parseFloat(el.attr('font-size'));
+        el.attr('font-size', (size * size_multiplier) + 'px')
+            .attr('fill', fill);
+    }
+
+    export function set_element_text(element, text) {
+        d3.select(element).text(text);
+    }
+
+    export function remove_element(element) {
+        d3.select(element).remove();
+    }
+
+    export function read_frequency_data(analyser, array) {
+        analyser.getFloatFrequencyData(array);
+    }
+
+    export function set_pointer_events(element, enabled) {
+        d3.select(element).style('pointer-events', enabled ? null : 'none');
+    }
+
     export function bring_to_front(element) {
         const node = d3.select(element).node();
         if (node && node.parentNode) {
@@ -258,9 +534,18 @@ const SKEW_INTENSITY: f64 = 0.3; // Subtle skew to keep letters more upright
 extern "C" {
     fn check_d3_available() -> bool;
     fn create_svg(container_id: &str, width: f64, height: f64) -> JsValue;
-    fn update_svg_size(width: f64, height: f64);
+    fn update_svg_size(svg: &JsValue, width: f64, height: f64);
     fn create_sphere(svg: &JsValue, cx: f64, cy: f64, radius: f64) -> JsValue;
     fn update_sphere_position(sphere: &JsValue, cx: f64, cy: f64);
+    fn update_sphere_radius(sphere: &JsValue, radius: f64);
+    fn update_sphere_gradient(svg: &JsValue, highlight: &str, mid: &str, shadow: &str);
+    fn update_sphere_outline(svg: &JsValue, color: &str, width: f64);
+    fn save_to_local_storage(key: &str, value: &str);
+    fn load_from_local_storage(key: &str) -> JsValue;
+    fn remove_from_local_storage(key: &str);
+    fn update_background(svg: &JsValue, fill: &str);
+    fn update_background_gradient(svg: &JsValue, top: &str, bottom: &str);
+    fn remove_background(svg: &JsValue);
     fn update_debug_lines(svg: &JsValue, center_x: f64, center_y: f64, width: f64, height: f64);
     fn create_orbit_lines(
         svg: &JsValue,
@@ -294,6 +579,19 @@ extern "C" {
         scale_x: f64,
         skew_x: f64,
     );
+    fn create_glow_filter(svg: &JsValue, id: &str, blur_std_dev: f64) -> JsValue;
+    fn update_glow_filter(filter: &JsValue, blur_std_dev: f64);
+    fn set_element_filter(element: &JsValue, id: &str);
+    fn update_text_color(element: &JsValue, fill: &str);
+    fn update_text_highlight(element: &JsValue, size_multiplier: f64, fill: &str);
+    fn update_text_font(element: &JsValue, family: &str);
+    fn update_text_weight(element: &JsValue, weight: &str);
+    fn update_text_stroke(element: &JsValue, width: f64, color: &str, opacity: f64);
+    fn update_text_shadow(element: &JsValue, dx: f64, dy: f64, blur: f64, color: &str);
+    fn set_element_text(element: &JsValue, text: &str);
+    fn remove_element(element: &JsValue);
+    fn set_pointer_events(element: &JsValue, enabled: bool);
+    fn read_frequency_data(analyser: &web_sys::AnalyserNode, array: &js_sys::Float32Array);
     fn reorder_elements(elements: &js_sys::Array);
     fn get_window_size() -> JsValue;
     fn create_debug_lines(
@@ -307,336 +605,9578 @@ extern "C" {
 }
 
 // ============================================================================
-// Color Utilities
+// Renderers
 // ============================================================================
 
-fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
-    let c = v * s;
-    let h_prime = h / 60.0;
-    let x = c * (1.0 - ((h_prime % 2.0) - 1.0).abs());
-    let m = v - c;
-
-    let (r, g, b) = if h_prime < 1.0 {
-        (c, x, 0.0)
-    } else if h_prime < 2.0 {
-        (x, c, 0.0)
-    } else if h_prime < 3.0 {
-        (0.0, c, x)
-    } else if h_prime < 4.0 {
-        (0.0, x, c)
-    } else if h_prime < 5.0 {
-        (x, 0.0, c)
-    } else {
-        (c, 0.0, x)
-    };
-
-    (
-        ((r + m) * 255.0) as u8,
-        ((g + m) * 255.0) as u8,
-        ((b + m) * 255.0) as u8,
-    )
+/// Which drawing surface a sphere renders to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RenderBackend {
+    Svg,    // One persistent <text> element per character, reordered in the DOM
+    Canvas, // A <canvas> cleared and redrawn from a draw list every frame
+    WebGl,  // Textured quads on a WebGL canvas, one draw call per frame
 }
 
-fn get_color_for_index(index: usize, total: usize) -> String {
-    let hue = (index as f64 / total as f64) * 360.0;
-    let (r, g, b) = hsv_to_rgb(hue, 0.8, 0.95);
-    format!("rgb({},{},{})", r, g, b)
+/// A character (or the sphere) as the renderer knows it: an SVG element, or
+/// a slot in the draw list of the canvas or WebGL renderer
+#[derive(Clone, Debug)]
+enum CharHandle {
+    Svg(JsValue),
+    Canvas(usize),
 }
 
-// ============================================================================
-// Type Aliases
-// ============================================================================
-
-/// Character render data: (index, screen_x, screen_y, font_size, opacity, z, scale_x, skew)
-type CharRenderData = (usize, f64, f64, f64, f64, f64, f64, f64);
+impl CharHandle {
+    /// The SVG element, for the SVG-only effects (glow filters)
+    fn element(&self) -> Option<&JsValue> {
+        match self {
+            CharHandle::Svg(element) => Some(element),
+            CharHandle::Canvas(_) => None,
+        }
+    }
+}
 
-// ============================================================================
-// Character Data Structure
-// ============================================================================
+/// Where and how large to draw a character this frame
+#[derive(Clone, Copy, Debug)]
+struct Placement {
+    x: f64,
+    y: f64,
+    font_size: f64,
+    opacity: f64,
+    scale_x: f64,
+    skew: f64, // Degrees
+}
 
-struct Character {
-    element: JsValue,
-    base_angle: f64, // Position along orbit (0-2π)
+impl Placement {
+    /// Parked out of sight (pooled ticker characters)
+    const HIDDEN: Placement = Placement {
+        x: 0.0,
+        y: 0.0,
+        font_size: 0.0,
+        opacity: 0.0,
+        scale_x: 1.0,
+        skew: 0.0,
+    };
 }
 
-// ============================================================================
-// TextSphere - Main Application Logic
-// ============================================================================
+/// Everything TextSphere draws goes through this
+trait Renderer {
+    fn backend(&self) -> RenderBackend;
+    /// The root element: target of pointer listeners and custom events
+    fn surface(&self) -> &JsValue;
+    fn resize(&self, width: f64, height: f64);
+    fn create_character(&self, glyph: &str, fill: &str, placement: &Placement) -> CharHandle;
+    fn update_character(&self, handle: &CharHandle, placement: &Placement);
+    fn set_character_text(&self, handle: &CharHandle, glyph: &str);
+    fn set_character_fill(&self, handle: &CharHandle, fill: &str);
+    fn set_character_font(&self, handle: &CharHandle, family: &str, weight: &str);
+    fn set_character_stroke(&self, handle: &CharHandle, width: f64, color: &str, opacity: f64);
+    /// Drop shadow offset by `(dx, dy)` px; all zeros removes it
+    fn set_character_shadow(&self, handle: &CharHandle, dx: f64, dy: f64, blur: f64, color: &str);
+    /// Scale the font size set by the last update_character and recolor
+    fn set_character_highlight(&self, handle: &CharHandle, size_multiplier: f64, fill: &str);
+    fn create_sphere(&self, cx: f64, cy: f64, radius: f64) -> CharHandle;
+    fn update_sphere(&self, handle: &CharHandle, cx: f64, cy: f64, radius: f64);
+    fn remove(&self, handle: &CharHandle);
+    /// Paint order, back to front. Handles left out are not drawn on a canvas.
+    fn reorder(&self, handles: &[&CharHandle]);
+    /// Fill behind everything with a CSS color; an empty string removes it
+    fn set_background(&self, color: &str);
+    /// Fill behind everything with a vertical gradient
+    fn set_background_gradient(&self, top: &str, bottom: &str);
+    fn set_sphere_gradient(&self, highlight: &str, mid: &str, shadow: &str);
+    /// Stroke around the sphere; a width of 0 removes it
+    fn set_sphere_outline(&self, color: &str, width: f64);
+    /// Overlay text in the top-left corner (the FPS counter); empty hides it
+    fn set_status_text(&self, text: &str);
+}
 
-struct TextSphere {
-    #[allow(dead_code)] // Kept for potential future use
+/// The original d3-driven SVG rendering
+struct SvgRenderer {
     svg: JsValue,
-    sphere: JsValue,
-    characters: Vec<Character>,
-    current_angle: f64,
-    center_x: f64,
-    center_y: f64,
 }
 
-impl TextSphere {
-    fn new() -> Option<Self> {
-        if !check_d3_available() {
-            log::error!("d3.js is not available");
-            return None;
+impl SvgRenderer {
+    fn new(container_id: &str, width: f64, height: f64) -> Self {
+        SvgRenderer {
+            svg: create_svg(container_id, width, height),
         }
+    }
+}
 
-        let window = web_sys::window()?;
-        let width = window.inner_width().ok()?.as_f64()?;
-        let height = window.inner_height().ok()?.as_f64()?;
-
-        let center_x = width / 2.0;
-        let center_y = height / 2.0;
-
-        // Create SVG (returns node reference used for creating child elements)
-        let svg = create_svg("app", width, height);
-
-        // Create central sphere (appended to svg)
-        let sphere = create_sphere(&svg, center_x, center_y, SPHERE_RADIUS);
-
-        // Create characters
-        let chars: Vec<char> = TEXT_TO_DISPLAY.chars().filter(|c| *c != ' ').collect();
-        let char_count = chars.len();
-        let mut characters = Vec::with_capacity(char_count);
+impl Renderer for SvgRenderer {
+    fn backend(&self) -> RenderBackend {
+        RenderBackend::Svg
+    }
 
-        for (i, ch) in chars.iter().enumerate() {
-            // Position along the orbit (0 to 2π)
-            let base_angle = (PI / 2.0) - (i as f64 / char_count as f64) * 2.0 * PI;
-            let color = get_color_for_index(i, char_count);
+    fn surface(&self) -> &JsValue {
+        &self.svg
+    }
 
-            // Calculate initial position (at base_angle)
-            let x = ORBIT_RADIUS * base_angle.cos();
-            let z = ORBIT_RADIUS * base_angle.sin();
+    fn resize(&self, width: f64, height: f64) {
+        update_svg_size(&self.svg, width, height);
+    }
 
-            // Project to 2D
-            let scale = PERSPECTIVE_DISTANCE / (PERSPECTIVE_DISTANCE + z);
-            let screen_x = center_x + x;
-            let screen_y = center_y;
-            let font_size = LETTER_SIZE * scale;
-
-            let element = create_text_element(
-                &svg,
-                screen_x,
-                screen_y,
-                &ch.to_string(),
-                &color,
-                font_size,
-                0.0,
-            );
+    fn create_character(&self, glyph: &str, fill: &str, placement: &Placement) -> CharHandle {
+        CharHandle::Svg(create_text_element(
+            &self.svg,
+            placement.x,
+            placement.y,
+            glyph,
+            fill,
+            placement.font_size,
+            placement.skew,
+        ))
+    }
 
-            characters.push(Character {
+    fn update_character(&self, handle: &CharHandle, placement: &Placement) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_element(
                 element,
-                base_angle,
-            });
+                placement.x,
+                placement.y,
+                placement.font_size,
+                placement.opacity,
+                placement.scale_x,
+                placement.skew,
+            );
         }
-
-        Some(TextSphere {
-            svg,
-            sphere,
-            characters,
-            current_angle: 0.0,
-            center_x,
-            center_y,
-        })
     }
 
-    fn animate(&mut self, delta: f64) {
-        // Update rotation angle
-        self.current_angle += ROTATION_SPEED * delta;
-
-        // Keep angle in reasonable range
-        if self.current_angle > 2.0 * PI {
-            self.current_angle -= 2.0 * PI;
+    fn set_character_text(&self, handle: &CharHandle, glyph: &str) {
+        if let CharHandle::Svg(element) = handle {
+            set_element_text(element, glyph);
         }
+    }
 
-        // Calculate positions using base interpolation
-        let mut char_data: Vec<CharRenderData> = Vec::new();
+    fn set_character_fill(&self, handle: &CharHandle, fill: &str) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_color(element, fill);
+        }
+    }
 
-        for (i, character) in self.characters.iter().enumerate() {
-            let angle = character.base_angle + self.current_angle;
-
-            // 3D position (orbiting in XZ plane)
-            let x = ORBIT_RADIUS * angle.cos();
-            let z = ORBIT_RADIUS * angle.sin();
-
-            // Perspective projection
-            // z > 0 = in front of center (closer to viewer), z < 0 = behind
-            let scale = PERSPECTIVE_DISTANCE / (PERSPECTIVE_DISTANCE - z);
-
-            // Project position - centered at screen
-            let screen_x = self.center_x + x;
-            let screen_y = self.center_y;
-            let font_size = LETTER_SIZE * scale;
-
-            // Characters face outward from sphere center (radially)
-            // Width scale = cos(angle from front) = z / R
-            // This naturally gives:
-            //   - Front (z = R): scale = 1.0 (full width, facing camera)
-            //   - Sides (z = 0): scale = 0.0 (edge-on)
-            //   - Back (z = -R): scale = -1.0 (full width, flipped/mirrored)
-            let scale_x = z / ORBIT_RADIUS;
-
-            // Calculate skew for "facing outward" effect
-            // Letters are painted on the sphere surface, facing radially outward
-            // The skew simulates viewing the letter's tangent plane at an angle
-            //
-            // For a letter at position (x, z):
-            // - At front (z = max, x = 0): faces camera directly → no skew
-            // - Moving right (z > 0, x > 0): left edge closer to camera → negative skew
-            // - Moving left (z > 0, x < 0): right edge closer to camera → positive skew
-            // - At back (z < 0): flipped, skew direction reverses
-            let current_skew = if z.abs() > 1.0 {
-                // atan(x/z) gives the angle between the letter normal and camera direction
-                // Convert to degrees and scale by intensity
-                let skew_radians = (x / z).atan();
-                let skew_degrees = skew_radians * (180.0 / PI);
-                // Negative because SVG skewX shifts top-right for positive angles
-                // and we want the near edge (toward camera) to appear larger
-                -skew_degrees * SKEW_INTENSITY
-            } else {
-                // Near edge-on (z ≈ 0), letter is nearly invisible anyway
-                0.0
-            };
+    fn set_character_font(&self, handle: &CharHandle, family: &str, weight: &str) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_font(element, family);
+            update_text_weight(element, weight);
+        }
+    }
 
-            char_data.push((
-                i,
-                screen_x,
-                screen_y,
-                font_size,
-                1.0,
-                z,
-                scale_x,
-                current_skew,
-            ));
+    fn set_character_stroke(&self, handle: &CharHandle, width: f64, color: &str, opacity: f64) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_stroke(element, width, color, opacity);
         }
+    }
 
-        // Sort by z (back to front - lowest z first, will be rendered first/behind)
-        char_data.sort_by(|a, b| a.5.partial_cmp(&b.5).unwrap());
+    fn set_character_shadow(&self, handle: &CharHandle, dx: f64, dy: f64, blur: f64, color: &str) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_shadow(element, dx, dy, blur, color);
+        }
+    }
 
-        // Update all character positions
-        for (i, screen_x, screen_y, font_size, _opacity, _z, scale_x, current_skew) in &char_data {
-            update_text_element(
-                &self.characters[*i].element,
-                *screen_x,
-                *screen_y,
-                *font_size,
-                1.0, // Always fully visible
-                *scale_x,
-                *current_skew,
-            );
+    fn set_character_highlight(&self, handle: &CharHandle, size_multiplier: f64, fill: &str) {
+        if let CharHandle::Svg(element) = handle {
+            update_text_highlight(element, size_multiplier, fill);
         }
+    }
 
-        // Reorder elements in DOM for proper z-ordering (back to front)
-        // char_data is sorted by z ascending (most negative/furthest first)
-        // Only include visible characters (opacity > 0) to avoid z-ordering issues
-        let elements = js_sys::Array::new();
+    fn create_sphere(&self, cx: f64, cy: f64, radius: f64) -> CharHandle {
+        CharHandle::Svg(create_sphere(&self.svg, cx, cy, radius))
+    }
 
-        let mut sphere_added = false;
-        for (i, _screen_x, _screen_y, _font_size, _opacity, z, _scale_x, _current_skew) in
-            &char_data
-        {
-            // Add sphere when transitioning from behind to in-front (z > 0)
-            if !sphere_added && *z > 0.0 {
-                elements.push(&self.sphere);
-                sphere_added = true;
-            }
-            elements.push(&self.characters[*i].element);
+    fn update_sphere(&self, handle: &CharHandle, cx: f64, cy: f64, radius: f64) {
+        if let CharHandle::Svg(sphere) = handle {
+            update_sphere_position(sphere, cx, cy);
+            update_sphere_radius(sphere, radius);
         }
+    }
 
-        // If all characters are behind sphere, add sphere last (on top)
-        if !sphere_added {
-            elements.push(&self.sphere);
+    fn remove(&self, handle: &CharHandle) {
+        if let CharHandle::Svg(element) = handle {
+            remove_element(element);
         }
+    }
 
+    fn reorder(&self, handles: &[&CharHandle]) {
+        let elements: js_sys::Array = handles.iter().filter_map(|h| h.element()).collect();
         reorder_elements(&elements);
     }
 
-    fn resize(&mut self) {
-        if let Some(window) = web_sys::window() {
-            if let (Ok(width), Ok(height)) = (window.inner_width(), window.inner_height()) {
-                if let (Some(w), Some(h)) = (width.as_f64(), height.as_f64()) {
-                    self.center_x = w / 2.0;
-                    self.center_y = h / 2.0;
-
-                    update_svg_size(w, h);
-                    update_sphere_position(&self.sphere, self.center_x, self.center_y);
-                }
-            }
+    fn set_background(&self, color: &str) {
+        if color.is_empty() {
+            remove_background(&self.svg);
+        } else {
+            update_background(&self.svg, color);
         }
     }
+
+    fn set_background_gradient(&self, top: &str, bottom: &str) {
+        update_background_gradient(&self.svg, top, bottom);
+    }
+
+    fn set_sphere_gradient(&self, highlight: &str, mid: &str, shadow: &str) {
+        update_sphere_gradient(&self.svg, highlight, mid, shadow);
+    }
+
+    fn set_sphere_outline(&self, color: &str, width: f64) {
+        update_sphere_outline(&self.svg, color, width);
+    }
+
+    fn set_status_text(&self, text: &str) {
+        update_debug_info(&self.svg, text);
+    }
 }
 
-// ============================================================================
-// Animation Loop
-// ============================================================================
+/// A character in a draw list
+struct DrawText {
+    glyph: String,
+    fill: String,
+    placement: Placement,
+    size_multiplier: f64,
+    font_family: String,
+    font_weight: String,
+    stroke: Option<(f64, String, f64)>, // (width, color, opacity)
+    shadow: Option<(f64, f64, f64, String)>, // (dx, dy, blur, color)
+}
 
-fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) {
-    web_sys::window()
-        .expect("no window")
-        .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("should register `requestAnimationFrame`");
+enum DrawBackground {
+    Fill(String),
+    Gradient(String, String), // (top, bottom)
 }
 
-#[allow(clippy::type_complexity)]
-fn start_animation_loop(text_sphere: Rc<RefCell<TextSphere>>) {
-    let f: Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>> = Rc::new(RefCell::new(None));
-    let g = f.clone();
+enum DrawItem {
+    Text(Box<DrawText>),
+    Sphere { cx: f64, cy: f64, radius: f64 },
+    Free, // Removed; the slot is reused by the next create
+}
 
-    let last_time: Rc<RefCell<f64>> = Rc::new(RefCell::new(0.0));
+/// Scene state kept in Rust for the renderers that repaint everything each
+/// frame. `CharHandle::Canvas` indexes `items`.
+struct DrawList {
+    items: Vec<DrawItem>,
+    free_slots: Vec<usize>,
+    background: Option<DrawBackground>,
+    sphere_colors: [String; 3], // (highlight, mid, shadow) gradient stops
+    sphere_outline: Option<(String, f64)>, // (color, width)
+    status_text: String,
+}
 
-    let text_sphere_clone = text_sphere.clone();
-    let last_time_clone = last_time.clone();
+impl DrawList {
+    fn new() -> Self {
+        DrawList {
+            items: Vec::new(),
+            free_slots: Vec::new(),
+            background: None,
+            sphere_colors: ["#6699ff", "#3366cc", "#1a3366"].map(|color| color.to_string()),
+            sphere_outline: None,
+            status_text: String::new(),
+        }
+    }
 
-    *g.borrow_mut() = Some(Closure::wrap(Box::new(move |time: f64| {
-        let mut last = last_time_clone.borrow_mut();
-        let delta = if *last == 0.0 {
-            0.016 // Assume ~60fps for first frame
-        } else {
-            (time - *last) / 1000.0
+    /// Store an item in a free slot (or a new one) and return its handle
+    fn insert(&mut self, item: DrawItem) -> CharHandle {
+        match self.free_slots.pop() {
+            Some(slot) => {
+                self.items[slot] = item;
+                CharHandle::Canvas(slot)
+            }
+            None => {
+                self.items.push(item);
+                CharHandle::Canvas(self.items.len() - 1)
+            }
+        }
+    }
+
+    fn insert_text(&mut self, glyph: &str, fill: &str, placement: &Placement) -> CharHandle {
+        self.insert(DrawItem::Text(Box::new(DrawText {
+            glyph: glyph.to_string(),
+            fill: fill.to_string(),
+            placement: *placement,
+            size_multiplier: 1.0,
+            font_family: FONT_FAMILY.to_string(),
+            font_weight: FONT_WEIGHT.to_string(),
+            stroke: None,
+            shadow: None,
+        })))
+    }
+
+    /// The text at a handle, if that is what it holds
+    fn text_mut(&mut self, handle: &CharHandle) -> Option<&mut DrawText> {
+        let CharHandle::Canvas(slot) = handle else {
+            return None;
         };
-        *last = time;
+        match self.items.get_mut(*slot) {
+            Some(DrawItem::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
 
-        text_sphere_clone.borrow_mut().animate(delta);
+    fn get(&self, handle: &CharHandle) -> Option<&DrawItem> {
+        match handle {
+            CharHandle::Canvas(slot) => self.items.get(*slot),
+            CharHandle::Svg(_) => None,
+        }
+    }
 
-        request_animation_frame(f.borrow().as_ref().unwrap());
-    }) as Box<dyn FnMut(f64)>));
+    fn update_sphere(&mut self, handle: &CharHandle, cx: f64, cy: f64, radius: f64) {
+        let CharHandle::Canvas(slot) = handle else {
+            return;
+        };
+        if let Some(item @ DrawItem::Sphere { .. }) = self.items.get_mut(*slot) {
+            *item = DrawItem::Sphere { cx, cy, radius };
+        }
+    }
 
-    request_animation_frame(g.borrow().as_ref().unwrap());
+    fn remove(&mut self, handle: &CharHandle) {
+        let CharHandle::Canvas(slot) = handle else {
+            return;
+        };
+        if let Some(item) = self.items.get_mut(*slot) {
+            if !matches!(item, DrawItem::Free) {
+                *item = DrawItem::Free;
+                self.free_slots.push(*slot);
+            }
+        }
+    }
 }
 
-// ============================================================================
-// Resize Handler
-// ============================================================================
+/// Draws onto a `<canvas>` with the 2D context. Nothing persists per
+/// character in the DOM: `reorder` clears the canvas and replays the draw
+/// list in the order given.
+struct CanvasRenderer {
+    canvas: web_sys::HtmlCanvasElement,
+    surface: JsValue,
+    context: web_sys::CanvasRenderingContext2d,
+    list: RefCell<DrawList>,
+}
 
-fn setup_resize_handler(text_sphere: Rc<RefCell<TextSphere>>) {
-    let closure = Closure::wrap(Box::new(move || {
-        text_sphere.borrow_mut().resize();
-    }) as Box<dyn Fn()>);
+impl CanvasRenderer {
+    fn new(container: &web_sys::Element, width: f64, height: f64) -> Result<Self, JsValue> {
+        let document = container
+            .owner_document()
+            .ok_or_else(|| JsValue::from_str("container is not in a document"))?;
+        let canvas: web_sys::HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+        canvas.set_id(&format!("{}-canvas", container.id()));
+        canvas.set_width(width as u32);
+        canvas.set_height(height as u32);
+        container.append_child(&canvas)?;
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("2d canvas context unavailable"))?
+            .dyn_into()?;
+        Ok(CanvasRenderer {
+            surface: canvas.clone().into(),
+            canvas,
+            context,
+            list: RefCell::new(DrawList::new()),
+        })
+    }
 
-    web_sys::window()
-        .expect("no window")
-        .add_event_listener_with_callback("resize", closure.as_ref().unchecked_ref())
-        .expect("should add resize listener");
+    fn draw_text(&self, text: &DrawText) {
+        let p = &text.placement;
+        let font_size = p.font_size * text.size_multiplier;
+        if p.opacity <= 0.0 || font_size <= 0.0 {
+            return;
+        }
+        let ctx = &self.context;
+        ctx.save();
+        ctx.set_global_alpha(p.opacity);
+        // Same transform as the SVG path: scale and skew around the text's position
+        let _ = ctx.translate(p.x, p.y);
+        let _ = ctx.scale(p.scale_x, 1.0);
+        let _ = ctx.transform(1.0, 0.0, p.skew.to_radians().tan(), 1.0, 0.0, 0.0);
+        ctx.set_font(&format!(
+            "{} {}px {}",
+            text.font_weight, font_size, text.font_family
+        ));
+        ctx.set_text_align("center");
+        ctx.set_text_baseline("middle");
+        if let Some((dx, dy, blur, color)) = &text.shadow {
+            ctx.set_shadow_offset_x(*dx);
+            ctx.set_shadow_offset_y(*dy);
+            ctx.set_shadow_blur(*blur);
+            ctx.set_shadow_color(color);
+        }
+        if let Some((width, color, opacity)) = &text.stroke {
+            // Stroke first so it doesn't eat into the fill
+            ctx.set_global_alpha(p.opacity * opacity);
+            ctx.set_line_width(*width);
+            ctx.set_stroke_style_str(color);
+            let _ = ctx.stroke_text(&text.glyph, 0.0, 0.0);
+            ctx.set_global_alpha(p.opacity);
+        }
+        ctx.set_fill_style_str(&text.fill);
+        let _ = ctx.fill_text(&text.glyph, 0.0, 0.0);
+        ctx.restore();
+    }
 
-    closure.forget();
-}
+    fn draw_background(&self, background: &DrawBackground, width: f64, height: f64) {
+        let ctx = &self.context;
+        match background {
+            DrawBackground::Fill(color) => ctx.set_fill_style_str(color),
+            DrawBackground::Gradient(top, bottom) => {
+                let gradient = ctx.create_linear_gradient(0.0, 0.0, 0.0, height);
+                let _ = gradient.add_color_stop(0.0, top);
+                let _ = gradient.add_color_stop(1.0, bottom);
+                ctx.set_fill_style_canvas_gradient(&gradient);
+            }
+        }
+        ctx.fill_rect(0.0, 0.0, width, height);
+    }
 
-// ============================================================================
-// Entry Point
-// ============================================================================
+    fn draw_status(&self, text: &str) {
+        // Same placement and style as the SVG #debug-info text
+        let ctx = &self.context;
+        ctx.set_font("12px monospace");
+        ctx.set_text_align("left");
+        ctx.set_text_baseline("alphabetic");
+        ctx.set_fill_style_str("#ffffff");
+        let _ = ctx.fill_text(text, 10.0, 20.0);
+    }
 
-#[wasm_bindgen(start)]
-pub fn main() {
-    wasm_logger::init(wasm_logger::Config::default());
-    log::info!("d3-text-sphere starting...");
+    fn draw_sphere(&self, list: &DrawList, cx: f64, cy: f64, radius: f64) {
+        let ctx = &self.context;
+        // Matches the SVG radialGradient: focus at 35%/35%, radius 60% of the bounding box
+        let (fx, fy) = (cx - 0.3 * radius, cy - 0.3 * radius);
+        let Ok(gradient) = ctx.create_radial_gradient(fx, fy, 0.0, fx, fy, 1.2 * radius) else {
+            return;
+        };
+        for (offset, color) in [0.0, 0.7, 1.0].into_iter().zip(&list.sphere_colors) {
+            let _ = gradient.add_color_stop(offset, color);
+        }
+        ctx.begin_path();
+        let _ = ctx.arc(cx, cy, radius, 0.0, TAU);
+        ctx.set_fill_style_canvas_gradient(&gradient);
+        ctx.fill();
+        if let Some((color, width)) = &list.sphere_outline {
+            ctx.set_line_width(*width);
+            ctx.set_stroke_style_str(color);
+            ctx.stroke();
+        }
+    }
+}
 
-    if let Some(text_sphere) = TextSphere::new() {
-        let text_sphere = Rc::new(RefCell::new(text_sphere));
+impl Renderer for CanvasRenderer {
+    fn backend(&self) -> RenderBackend {
+        RenderBackend::Canvas
+    }
 
-        setup_resize_handler(text_sphere.clone());
-        start_animation_loop(text_sphere);
+    fn surface(&self) -> &JsValue {
+        &self.surface
+    }
 
-        log::info!("d3-text-sphere running");
+    fn resize(&self, width: f64, height: f64) {
+        self.canvas.set_width(width as u32);
+        self.canvas.set_height(height as u32);
+    }
+
+    fn create_character(&self, glyph: &str, fill: &str, placement: &Placement) -> CharHandle {
+        self.list.borrow_mut().insert_text(glyph, fill, placement)
+    }
+
+    fn update_character(&self, handle: &CharHandle, placement: &Placement) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.placement = *placement;
+            text.size_multiplier = 1.0;
+        }
+    }
+
+    fn set_character_text(&self, handle: &CharHandle, glyph: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.glyph = glyph.to_string();
+        }
+    }
+
+    fn set_character_fill(&self, handle: &CharHandle, fill: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.fill = fill.to_string();
+        }
+    }
+
+    fn set_character_font(&self, handle: &CharHandle, family: &str, weight: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.font_family = family.to_string();
+            text.font_weight = weight.to_string();
+        }
+    }
+
+    fn set_character_stroke(&self, handle: &CharHandle, width: f64, color: &str, opacity: f64) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.stroke = (width > 0.0).then(|| (width, color.to_string(), opacity));
+        }
+    }
+
+    fn set_character_shadow(&self, handle: &CharHandle, dx: f64, dy: f64, blur: f64, color: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            let visible = dx != 0.0 || dy != 0.0 || blur != 0.0;
+            text.shadow = visible.then(|| (dx, dy, blur, color.to_string()));
+        }
+    }
+
+    fn set_character_highlight(&self, handle: &CharHandle, size_multiplier: f64, fill: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.size_multiplier = size_multiplier;
+            text.fill = fill.to_string();
+        }
+    }
+
+    fn create_sphere(&self, cx: f64, cy: f64, radius: f64) -> CharHandle {
+        self.list
+            .borrow_mut()
+            .insert(DrawItem::Sphere { cx, cy, radius })
+    }
+
+    fn update_sphere(&self, handle: &CharHandle, cx: f64, cy: f64, radius: f64) {
+        self.list.borrow_mut().update_sphere(handle, cx, cy, radius);
+    }
+
+    fn remove(&self, handle: &CharHandle) {
+        self.list.borrow_mut().remove(handle);
+    }
+
+    fn reorder(&self, handles: &[&CharHandle]) {
+        let (width, height) = (self.canvas.width() as f64, self.canvas.height() as f64);
+        self.context.clear_rect(0.0, 0.0, width, height);
+        let list = self.list.borrow();
+        if let Some(background) = &list.background {
+            self.draw_background(background, width, height);
+        }
+        for handle in handles {
+            match list.get(handle) {
+                Some(DrawItem::Text(text)) => self.draw_text(text),
+                Some(&DrawItem::Sphere { cx, cy, radius }) => {
+                    self.draw_sphere(&list, cx, cy, radius)
+                }
+                _ => {}
+            }
+        }
+        if !list.status_text.is_empty() {
+            self.draw_status(&list.status_text);
+        }
+    }
+
+    fn set_background(&self, color: &str) {
+        self.list.borrow_mut().background =
+            (!color.is_empty()).then(|| DrawBackground::Fill(color.to_string()));
+    }
+
+    fn set_background_gradient(&self, top: &str, bottom: &str) {
+        self.list.borrow_mut().background = Some(DrawBackground::Gradient(
+            top.to_string(),
+            bottom.to_string(),
+        ));
+    }
+
+    fn set_sphere_gradient(&self, highlight: &str, mid: &str, shadow: &str) {
+        self.list.borrow_mut().sphere_colors =
+            [highlight, mid, shadow].map(|color| color.to_string());
+    }
+
+    fn set_sphere_outline(&self, color: &str, width: f64) {
+        self.list.borrow_mut().sphere_outline = (width > 0.0).then(|| (color.to_string(), width));
+    }
+
+    fn set_status_text(&self, text: &str) {
+        self.list.borrow_mut().status_text = text.to_string();
+    }
+}
+
+type Gl = web_sys::WebGlRenderingContext;
+
+const ATLAS_SIZE: u32 = 1024; // Glyph atlas texture width and height (px)
+const ATLAS_FONT_SIZE: f64 = 64.0; // Glyphs are rasterized at this size and scaled per quad
+const ATLAS_PADDING: f64 = 2.0; // Gap between atlas cells so filtering doesn't bleed
+const WHITE_CELL: f64 = 4.0; // Solid white block at the atlas origin, for untextured quads
+const FLOATS_PER_VERTEX: i32 = 9; // x, y, depth, u, v, r, g, b, a
+
+const VERTEX_SHADER: &str = r#"
+    attribute vec3 a_position;
+    attribute vec2 a_uv;
+    attribute vec4 a_color;
+    uniform vec2 u_resolution;
+    varying vec2 v_uv;
+    varying vec4 v_color;
+
+    void main() {
+        // Pixels (y down) to clip space (y up); depth comes from the paint order
+        vec2 clip = a_position.xy / u_resolution * 2.0 - 1.0;
+        gl_Position = vec4(clip.x, -clip.y, a_position.z, 1.0);
+        v_uv = a_uv;
+        v_color = a_color;
+    }
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+    precision mediump float;
+    uniform sampler2D u_atlas;
+    varying vec2 v_uv;
+    varying vec4 v_color;
+
+    void main() {
+        // Glyphs are white in the atlas, so the vertex color tints them
+        gl_FragColor = v_color * texture2D(u_atlas, v_uv);
+    }
+"#;
+
+/// A rectangle of the glyph atlas: texture coordinates plus its size in
+/// atlas pixels
+#[derive(Clone, Copy)]
+struct AtlasCell {
+    u0: f32,
+    v0: f32,
+    u1: f32,
+    v1: f32,
+    width: f64,
+    height: f64,
+}
+
+/// Off-screen 2D canvas that glyphs (and the sphere) are rasterized into
+/// once, then uploaded as the WebGL texture. Cells are packed in rows;
+/// when it fills up the atlas is cleared and repopulated.
+struct GlyphAtlas {
+    canvas: web_sys::HtmlCanvasElement,
+    context: web_sys::CanvasRenderingContext2d,
+    cells: HashMap<String, AtlasCell>,
+    cursor_x: f64,
+    cursor_y: f64,
+    row_height: f64,
+    dirty: bool, // Changed since the last texture upload
+}
+
+impl GlyphAtlas {
+    fn new(document: &web_sys::Document) -> Result<Self, JsValue> {
+        let canvas: web_sys::HtmlCanvasElement = document.create_element("canvas")?.dyn_into()?;
+        canvas.set_width(ATLAS_SIZE);
+        canvas.set_height(ATLAS_SIZE);
+        let context = canvas
+            .get_context("2d")?
+            .ok_or_else(|| JsValue::from_str("2d canvas context unavailable"))?
+            .dyn_into()?;
+        let mut atlas = GlyphAtlas {
+            canvas,
+            context,
+            cells: HashMap::new(),
+            cursor_x: 0.0,
+            cursor_y: 0.0,
+            row_height: 0.0,
+            dirty: true,
+        };
+        atlas.clear();
+        Ok(atlas)
+    }
+
+    fn clear(&mut self) {
+        let size = ATLAS_SIZE as f64;
+        self.context.clear_rect(0.0, 0.0, size, size);
+        self.context.set_fill_style_str("#ffffff");
+        self.context.fill_rect(0.0, 0.0, WHITE_CELL, WHITE_CELL);
+        self.cells.clear();
+        self.cursor_x = WHITE_CELL + ATLAS_PADDING;
+        self.cursor_y = 0.0;
+        self.row_height = WHITE_CELL;
+        self.dirty = true;
+    }
+
+    /// The middle of the white block, for solid-colored quads
+    fn white(&self) -> AtlasCell {
+        let texel = (WHITE_CELL / 2.0 / ATLAS_SIZE as f64) as f32;
+        AtlasCell {
+            u0: texel,
+            v0: texel,
+            u1: texel,
+            v1: texel,
+            width: 0.0,
+            height: 0.0,
+        }
+    }
+
+    /// The cell for `key`, rasterizing it with `draw(context, x, y)` at a
+    /// newly reserved top-left corner if it isn't cached. None when the
+    /// atlas has no room left.
+    fn cell(
+        &mut self,
+        key: &str,
+        width: f64,
+        height: f64,
+        draw: impl FnOnce(&web_sys::CanvasRenderingContext2d, f64, f64),
+    ) -> Option<AtlasCell> {
+        if let Some(cell) = self.cells.get(key) {
+            return Some(*cell);
+        }
+        let size = ATLAS_SIZE as f64;
+        if self.cursor_x + width > size {
+            self.cursor_x = 0.0;
+            self.cursor_y += self.row_height + ATLAS_PADDING;
+            self.row_height = 0.0;
+        }
+        if width > size || self.cursor_y + height > size {
+            return None;
+        }
+
+        let (x, y) = (self.cursor_x, self.cursor_y);
+        draw(&self.context, x, y);
+        let cell = AtlasCell {
+            u0: (x / size) as f32,
+            v0: (y / size) as f32,
+            u1: ((x + width) / size) as f32,
+            v1: ((y + height) / size) as f32,
+            width,
+            height,
+        };
+        self.cursor_x += width + ATLAS_PADDING;
+        self.row_height = self.row_height.max(height);
+        self.cells.insert(key.to_string(), cell);
+        self.dirty = true;
+        Some(cell)
+    }
+
+    /// A glyph rasterized in white at ATLAS_FONT_SIZE in the given CSS font
+    /// (without the size)
+    fn glyph(&mut self, glyph: &str, weight: &str, family: &str) -> Option<AtlasCell> {
+        let font = format!("{} {}px {}", weight, ATLAS_FONT_SIZE, family);
+        let key = format!("{}\u{0}{}", font, glyph);
+        if let Some(cell) = self.cells.get(&key) {
+            return Some(*cell);
+        }
+        self.context.set_font(&font);
+        let width = self.context.measure_text(glyph).ok()?.width().ceil() + 2.0;
+        let height = (ATLAS_FONT_SIZE * 1.25).ceil();
+        self.cell(&key, width, height, |ctx, x, y| {
+            ctx.set_font(&font);
+            ctx.set_text_align("center");
+            ctx.set_text_baseline("middle");
+            ctx.set_fill_style_str("#ffffff");
+            let _ = ctx.fill_text(glyph, x + width / 2.0, y + height / 2.0);
+        })
+    }
+
+    /// The sphere in the given gradient colors, filling a square cell
+    fn sphere(&mut self, colors: &[String; 3]) -> Option<AtlasCell> {
+        let key = format!("sphere\u{0}{}", colors.join("\u{0}"));
+        let size = ATLAS_FONT_SIZE * 2.0;
+        self.cell(&key, size, size, |ctx, x, y| {
+            let radius = size / 2.0;
+            let (cx, cy) = (x + radius, y + radius);
+            // Matches the SVG radialGradient: focus at 35%/35%, radius 60% of the bounding box
+            let (fx, fy) = (cx - 0.3 * radius, cy - 0.3 * radius);
+            let Ok(gradient) = ctx.create_radial_gradient(fx, fy, 0.0, fx, fy, 1.2 * radius) else {
+                return;
+            };
+            for (offset, color) in [0.0, 0.7, 1.0].into_iter().zip(colors) {
+                let _ = gradient.add_color_stop(offset, color);
+            }
+            ctx.begin_path();
+            let _ = ctx.arc(cx, cy, radius, 0.0, TAU);
+            ctx.set_fill_style_canvas_gradient(&gradient);
+            ctx.fill();
+        })
+    }
+}
+
+/// RGBA in 0..=1 for a color from the draw list (`#rrggbb`, `#rgb` or
+/// `rgb(r,g,b)`), with the given alpha. Unparseable colors draw white.
+fn vertex_color(color: &str, alpha: f64) -> [f32; 4] {
+    let (r, g, b) = parse_rgb(color).unwrap_or((1.0, 1.0, 1.0));
+    [r as f32, g as f32, b as f32, alpha as f32]
+}
+
+/// Append two triangles covering `corners` (clockwise from top-left),
+/// colored `top` along the top edge and `bottom` along the bottom one
+fn push_quad(
+    vertices: &mut Vec<f32>,
+    corners: [(f64, f64); 4],
+    depth: f32,
+    cell: &AtlasCell,
+    top: [f32; 4],
+    bottom: [f32; 4],
+) {
+    let uvs = [
+        (cell.u0, cell.v0),
+        (cell.u1, cell.v0),
+        (cell.u1, cell.v1),
+        (cell.u0, cell.v1),
+    ];
+    let colors = [top, top, bottom, bottom];
+    for i in [0, 1, 2, 0, 2, 3] {
+        let (x, y) = corners[i];
+        let (u, v) = uvs[i];
+        vertices.extend_from_slice(&[x as f32, y as f32, depth, u, v]);
+        vertices.extend_from_slice(&colors[i]);
+    }
+}
+
+fn compile_shader(gl: &Gl, kind: u32, source: &str) -> Result<web_sys::WebGlShader, JsValue> {
+    let shader = gl
+        .create_shader(kind)
+        .ok_or_else(|| JsValue::from_str("could not create shader"))?;
+    gl.shader_source(&shader, source);
+    gl.compile_shader(&shader);
+    if gl
+        .get_shader_parameter(&shader, Gl::COMPILE_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        Ok(shader)
+    } else {
+        let log = gl.get_shader_info_log(&shader).unwrap_or_default();
+        Err(JsValue::from_str(&format!(
+            "shader failed to compile: {}",
+            log
+        )))
+    }
+}
+
+fn link_program(gl: &Gl) -> Result<web_sys::WebGlProgram, JsValue> {
+    let program = gl
+        .create_program()
+        .ok_or_else(|| JsValue::from_str("could not create program"))?;
+    gl.attach_shader(
+        &program,
+        &compile_shader(gl, Gl::VERTEX_SHADER, VERTEX_SHADER)?,
+    );
+    gl.attach_shader(
+        &program,
+        &compile_shader(gl, Gl::FRAGMENT_SHADER, FRAGMENT_SHADER)?,
+    );
+    gl.link_program(&program);
+    if gl
+        .get_program_parameter(&program, Gl::LINK_STATUS)
+        .as_bool()
+        .unwrap_or(false)
+    {
+        Ok(program)
+    } else {
+        let log = gl.get_program_info_log(&program).unwrap_or_default();
+        Err(JsValue::from_str(&format!(
+            "shader program failed to link: {}",
+            log
+        )))
+    }
+}
+
+/// Draws with WebGL: every character is a quad textured from the glyph
+/// atlas, and the whole frame (background, sphere, characters, FPS text)
+/// is one draw call. Paint order is written to the depth buffer. Stroke
+/// outlines and glow aren't drawn.
+struct WebGlRenderer {
+    canvas: web_sys::HtmlCanvasElement,
+    surface: JsValue,
+    gl: Gl,
+    resolution: Option<web_sys::WebGlUniformLocation>,
+    buffer: web_sys::WebGlBuffer,
+    texture: web_sys::WebGlTexture,
+    atlas: RefCell<GlyphAtlas>,
+    list: RefCell<DrawList>,
+}
+
+impl WebGlRenderer {
+    fn new(canvas: web_sys::HtmlCanvasElement) -> Result<Self, JsValue> {
+        let gl: Gl = canvas
+            .get_context("webgl")?
+            .ok_or_else(|| JsValue::from_str("WebGL is not available"))?
+            .dyn_into()?;
+        let document = canvas
+            .owner_document()
+            .ok_or_else(|| JsValue::from_str("canvas is not in a document"))?;
+        let atlas = GlyphAtlas::new(&document)?;
+
+        let program = link_program(&gl)?;
+        gl.use_program(Some(&program));
+
+        let buffer = gl
+            .create_buffer()
+            .ok_or_else(|| JsValue::from_str("could not create vertex buffer"))?;
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&buffer));
+        let stride = FLOATS_PER_VERTEX * 4;
+        for (name, size, offset) in [("a_position", 3, 0), ("a_uv", 2, 3), ("a_color", 4, 5)] {
+            let location = gl.get_attrib_location(&program, name);
+            if location < 0 {
+                return Err(JsValue::from_str(&format!("missing attribute {}", name)));
+            }
+            gl.enable_vertex_attrib_array(location as u32);
+            gl.vertex_attrib_pointer_with_i32(
+                location as u32,
+                size,
+                Gl::FLOAT,
+                false,
+                stride,
+                offset * 4,
+            );
+        }
+
+        let texture = gl
+            .create_texture()
+            .ok_or_else(|| JsValue::from_str("could not create atlas texture"))?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.uniform1i(gl.get_uniform_location(&program, "u_atlas").as_ref(), 0);
+
+        gl.enable(Gl::DEPTH_TEST);
+        gl.depth_func(Gl::LEQUAL);
+        gl.enable(Gl::BLEND);
+        // The canvas is composited as premultiplied alpha
+        gl.blend_func_separate(
+            Gl::SRC_ALPHA,
+            Gl::ONE_MINUS_SRC_ALPHA,
+            Gl::ONE,
+            Gl::ONE_MINUS_SRC_ALPHA,
+        );
+
+        Ok(WebGlRenderer {
+            resolution: gl.get_uniform_location(&program, "u_resolution"),
+            surface: canvas.clone().into(),
+            canvas,
+            gl,
+            buffer,
+            texture,
+            atlas: RefCell::new(atlas),
+            list: RefCell::new(DrawList::new()),
+        })
+    }
+
+    /// Vertices for the whole frame in paint order. Returns None if the
+    /// atlas ran out of room for a glyph.
+    fn build_vertices(
+        &self,
+        atlas: &mut GlyphAtlas,
+        list: &DrawList,
+        handles: &[&CharHandle],
+        width: f64,
+        height: f64,
+    ) -> Option<Vec<f32>> {
+        let mut vertices = Vec::with_capacity((handles.len() + 2) * 6 * FLOATS_PER_VERTEX as usize);
+        // Later quads get smaller depths, so they pass the LEQUAL test over earlier ones
+        let layers = (handles.len() + 2) as f32;
+        let depth = |layer: usize| 1.0 - 2.0 * (layer as f32 + 0.5) / layers;
+
+        if let Some(background) = &list.background {
+            let (top, bottom) = match background {
+                DrawBackground::Fill(color) => (color, color),
+                DrawBackground::Gradient(top, bottom) => (top, bottom),
+            };
+            push_quad(
+                &mut vertices,
+                [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)],
+                depth(0),
+                &atlas.white(),
+                vertex_color(top, 1.0),
+                vertex_color(bottom, 1.0),
+            );
+        }
+
+        for (i, handle) in handles.iter().enumerate() {
+            match list.get(handle) {
+                Some(DrawItem::Text(text)) => {
+                    let p = &text.placement;
+                    let k = p.font_size * text.size_multiplier / ATLAS_FONT_SIZE;
+                    if p.opacity <= 0.0 || k <= 0.0 {
+                        continue;
+                    }
+                    let cell = atlas.glyph(&text.glyph, &text.font_weight, &text.font_family)?;
+                    let (half_w, half_h) = (cell.width * k / 2.0, cell.height * k / 2.0);
+                    // Same transform as the SVG path: skew, then scale, around the position
+                    let tan = p.skew.to_radians().tan();
+                    let corner = |lx: f64, ly: f64| (p.x + p.scale_x * (lx + tan * ly), p.y + ly);
+                    let color = vertex_color(&text.fill, p.opacity);
+                    push_quad(
+                        &mut vertices,
+                        [
+                            corner(-half_w, -half_h),
+                            corner(half_w, -half_h),
+                            corner(half_w, half_h),
+                            corner(-half_w, half_h),
+                        ],
+                        depth(i + 1),
+                        &cell,
+                        color,
+                        color,
+                    );
+                }
+                Some(&DrawItem::Sphere { cx, cy, radius }) => {
+                    let mut radius = radius;
+                    if let Some((color, width)) = &list.sphere_outline {
+                        // A disc in the outline color behind a slightly smaller sphere
+                        let disc = atlas.sphere(&["#ffffff"; 3].map(String::from))?;
+                        let (left, right, top, bottom) = (
+                            cx - radius - width / 2.0,
+                            cx + radius + width / 2.0,
+                            cy - radius - width / 2.0,
+                            cy + radius + width / 2.0,
+                        );
+                        let color = vertex_color(color, 1.0);
+                        push_quad(
+                            &mut vertices,
+                            [(left, top), (right, top), (right, bottom), (left, bottom)],
+                            depth(i + 1),
+                            &disc,
+                            color,
+                            color,
+                        );
+                        radius = (radius - width / 2.0).max(0.0);
+                    }
+                    let cell = atlas.sphere(&list.sphere_colors)?;
+                    let (left, right, top, bottom) =
+                        (cx - radius, cx + radius, cy - radius, cy + radius);
+                    push_quad(
+                        &mut vertices,
+                        [(left, top), (right, top), (right, bottom), (left, bottom)],
+                        depth(i + 1),
+                        &cell,
+                        [1.0; 4],
+                        [1.0; 4],
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        // FPS counter, same placement and style as the SVG #debug-info text
+        let mut x = 10.0;
+        let k = 12.0 / ATLAS_FONT_SIZE;
+        for glyph in list.status_text.graphemes(true) {
+            let cell = atlas.glyph(glyph, "normal", "monospace")?;
+            let (w, h) = (cell.width * k, cell.height * k);
+            let (top, bottom) = (16.0 - h / 2.0, 16.0 + h / 2.0);
+            push_quad(
+                &mut vertices,
+                [(x, top), (x + w, top), (x + w, bottom), (x, bottom)],
+                depth(handles.len() + 1),
+                &cell,
+                [1.0; 4],
+                [1.0; 4],
+            );
+            x += w;
+        }
+
+        Some(vertices)
+    }
+}
+
+impl Renderer for WebGlRenderer {
+    fn backend(&self) -> RenderBackend {
+        RenderBackend::WebGl
+    }
+
+    fn surface(&self) -> &JsValue {
+        &self.surface
+    }
+
+    fn resize(&self, width: f64, height: f64) {
+        self.canvas.set_width(width as u32);
+        self.canvas.set_height(height as u32);
+        self.gl.viewport(0, 0, width as i32, height as i32);
+    }
+
+    fn create_character(&self, glyph: &str, fill: &str, placement: &Placement) -> CharHandle {
+        self.list.borrow_mut().insert_text(glyph, fill, placement)
+    }
+
+    fn update_character(&self, handle: &CharHandle, placement: &Placement) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.placement = *placement;
+            text.size_multiplier = 1.0;
+        }
+    }
+
+    fn set_character_text(&self, handle: &CharHandle, glyph: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.glyph = glyph.to_string();
+        }
+    }
+
+    fn set_character_fill(&self, handle: &CharHandle, fill: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.fill = fill.to_string();
+        }
+    }
+
+    fn set_character_font(&self, handle: &CharHandle, family: &str, weight: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.font_family = family.to_string();
+            text.font_weight = weight.to_string();
+        }
+    }
+
+    fn set_character_stroke(&self, _handle: &CharHandle, _width: f64, _color: &str, _opacity: f64) {
+        // Outlines would need a second atlas channel; not drawn
+    }
+
+    fn set_character_shadow(&self, _h: &CharHandle, _dx: f64, _dy: f64, _blur: f64, _color: &str) {
+        // Would need a blurred second pass; not drawn
+    }
+
+    fn set_character_highlight(&self, handle: &CharHandle, size_multiplier: f64, fill: &str) {
+        if let Some(text) = self.list.borrow_mut().text_mut(handle) {
+            text.size_multiplier = size_multiplier;
+            text.fill = fill.to_string();
+        }
+    }
+
+    fn create_sphere(&self, cx: f64, cy: f64, radius: f64) -> CharHandle {
+        self.list
+            .borrow_mut()
+            .insert(DrawItem::Sphere { cx, cy, radius })
+    }
+
+    fn update_sphere(&self, handle: &CharHandle, cx: f64, cy: f64, radius: f64) {
+        self.list.borrow_mut().update_sphere(handle, cx, cy, radius);
+    }
+
+    fn remove(&self, handle: &CharHandle) {
+        self.list.borrow_mut().remove(handle);
+    }
+
+    fn reorder(&self, handles: &[&CharHandle]) {
+        let (width, height) = (self.canvas.width() as f64, self.canvas.height() as f64);
+        let list = self.list.borrow();
+        let mut atlas = self.atlas.borrow_mut();
+        let vertices = match self.build_vertices(&mut atlas, &list, handles, width, height) {
+            Some(vertices) => vertices,
+            None => {
+                // Full: start the atlas over with just this frame's glyphs
+                atlas.clear();
+                match self.build_vertices(&mut atlas, &list, handles, width, height) {
+                    Some(vertices) => vertices,
+                    None => {
+                        log::warn!("glyph atlas is too small for this frame");
+                        return;
+                    }
+                }
+            }
+        };
+
+        let gl = &self.gl;
+        if atlas.dirty {
+            gl.bind_texture(Gl::TEXTURE_2D, Some(&self.texture));
+            let uploaded = gl.tex_image_2d_with_u32_and_u32_and_canvas(
+                Gl::TEXTURE_2D,
+                0,
+                Gl::RGBA as i32,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                &atlas.canvas,
+            );
+            if let Err(e) = uploaded {
+                log::warn!("could not upload glyph atlas: {:?}", e);
+            }
+            atlas.dirty = false;
+        }
+
+        gl.uniform2f(self.resolution.as_ref(), width as f32, height as f32);
+        gl.clear_color(0.0, 0.0, 0.0, 0.0);
+        gl.clear_depth(1.0);
+        gl.clear(Gl::COLOR_BUFFER_BIT | Gl::DEPTH_BUFFER_BIT);
+        gl.bind_buffer(Gl::ARRAY_BUFFER, Some(&self.buffer));
+        gl.buffer_data_with_array_buffer_view(
+            Gl::ARRAY_BUFFER,
+            &js_sys::Float32Array::from(vertices.as_slice()),
+            Gl::DYNAMIC_DRAW,
+        );
+        gl.draw_arrays(Gl::TRIANGLES, 0, vertices.len() as i32 / FLOATS_PER_VERTEX);
+    }
+
+    fn set_background(&self, color: &str) {
+        self.list.borrow_mut().background =
+            (!color.is_empty()).then(|| DrawBackground::Fill(color.to_string()));
+    }
+
+    fn set_background_gradient(&self, top: &str, bottom: &str) {
+        self.list.borrow_mut().background = Some(DrawBackground::Gradient(
+            top.to_string(),
+            bottom.to_string(),
+        ));
+    }
+
+    fn set_sphere_gradient(&self, highlight: &str, mid: &str, shadow: &str) {
+        self.list.borrow_mut().sphere_colors =
+            [highlight, mid, shadow].map(|color| color.to_string());
+    }
+
+    fn set_sphere_outline(&self, color: &str, width: f64) {
+        self.list.borrow_mut().sphere_outline = (width > 0.0).then(|| (color.to_string(), width));
+    }
+
+    fn set_status_text(&self, text: &str) {
+        self.list.borrow_mut().status_text = text.to_string();
+    }
+}
+
+// ============================================================================
+// Color Utilities
+// ============================================================================
+
+fn hsv_to_rgb(h: f64, s: f64, v: f64) -> (u8, u8, u8) {
+    let c = v * s;
+    let h_prime = h / 60.0;
+    let x = c * (1.0 - ((h_prime % 2.0) - 1.0).abs());
+    let m = v - c;
+
+    let (r, g, b) = if h_prime < 1.0 {
+        (c, x, 0.0)
+    } else if h_prime < 2.0 {
+        (x, c, 0.0)
+    } else if h_prime < 3.0 {
+        (0.0, c, x)
+    } else if h_prime < 4.0 {
+        (0.0, x, c)
+    } else if h_prime < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+
+    (
+        ((r + m) * 255.0) as u8,
+        ((g + m) * 255.0) as u8,
+        ((b + m) * 255.0) as u8,
+    )
+}
+
+/// Background fill for a user-supplied color: None for an empty string or
+/// `"transparent"`, otherwise the validated hex color
+fn background_fill(hex: &str) -> Result<Option<String>, String> {
+    let hex = hex.trim();
+    if hex.is_empty() || hex.eq_ignore_ascii_case("transparent") {
+        return Ok(None);
+    }
+    if hex_to_hsv(hex).is_none() {
+        return Err(format!("invalid background color: {}", hex));
+    }
+    Ok(Some(format!("#{}", hex.trim_start_matches('#'))))
+}
+
+/// Parse `#rrggbb` or `#rgb` (the `#` is optional) into RGB channels in 0..=1
+fn hex_to_rgb(hex: &str) -> Option<(f64, f64, f64)> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+    if !digits.is_ascii() {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok().map(|c| c as f64 / 255.0);
+    let (r, g, b) = match digits.len() {
+        6 => (
+            channel(&digits[0..2])?,
+            channel(&digits[2..4])?,
+            channel(&digits[4..6])?,
+        ),
+        // Each shorthand digit is doubled: #f64 = #ff6644
+        3 => (
+            channel(&digits[0..1].repeat(2))?,
+            channel(&digits[1..2].repeat(2))?,
+            channel(&digits[2..3].repeat(2))?,
+        ),
+        _ => return None,
+    };
+    Some((r, g, b))
+}
+
+/// RGB channels in 0..=1 of a color as generated here: hex or `rgb(r,g,b)`
+fn parse_rgb(color: &str) -> Option<(f64, f64, f64)> {
+    let Some(args) = color
+        .trim()
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    else {
+        return hex_to_rgb(color.trim());
+    };
+    let mut channels = args.split(',').map(|c| {
+        c.trim()
+            .parse::<f64>()
+            .ok()
+            .map(|c| c.clamp(0.0, 255.0) / 255.0)
+    });
+    let rgb = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(rgb)
+}
+
+/// Parse `#rrggbb` or `#rgb` (the `#` is optional) into HSV: hue in
+/// degrees, saturation and value in 0..=1
+pub fn hex_to_hsv(hex: &str) -> Option<(f64, f64, f64)> {
+    let (r, g, b) = hex_to_rgb(hex)?;
+    let max = r.max(g).max(b);
+    let range = max - r.min(g).min(b);
+    let hue = if range == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / range).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / range + 2.0)
+    } else {
+        60.0 * ((r - g) / range + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { range / max };
+    Some((hue, saturation, max))
+}
+
+/// Format an HSV color (hue in degrees, saturation and value in 0..=1) as `#rrggbb`
+pub fn hsv_to_hex(h: f64, s: f64, v: f64) -> String {
+    let (r, g, b) = hsv_to_rgb(h.rem_euclid(360.0), s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Character color for `hue`, with RGB scaled by `brightness` (1.0 = unshaded)
+fn get_color_for_hue(hue: f64, brightness: f64) -> String {
+    let (r, g, b) = hsv_to_rgb(
+        hue.rem_euclid(360.0),
+        0.8,
+        0.95 * brightness.clamp(0.0, 1.0),
+    );
+    format!("rgb({},{},{})", r, g, b)
+}
+
+/// Multiply each channel of a `#rrggbb` or `rgb(...)` color by `tint`
+/// (1.0 = unchanged), as `rgb(...)`. Unparseable colors are returned as is.
+fn tint_color(color: &str, tint: (f64, f64, f64)) -> String {
+    let Some((r, g, b)) = parse_rgb(color) else {
+        return color.to_string();
+    };
+    let channel = |c: f64, t: f64| (c * t * 255.0).round().clamp(0.0, 255.0) as u8;
+    format!(
+        "rgb({},{},{})",
+        channel(r, tint.0),
+        channel(g, tint.1),
+        channel(b, tint.2)
+    )
+}
+
+fn hue_for_index(index: usize, total: usize) -> f64 {
+    (index as f64 / total as f64) * 360.0
+}
+
+/// HSV color (hue in degrees, saturation and value in 0..=1) as `rgb(...)`
+fn hsv_color(h: f64, s: f64, v: f64) -> String {
+    let (r, g, b) = hsv_to_rgb(h.rem_euclid(360.0), s.clamp(0.0, 1.0), v.clamp(0.0, 1.0));
+    format!("rgb({},{},{})", r, g, b)
+}
+
+// ============================================================================
+// Color Schemes
+// ============================================================================
+
+/// Color of the character at `index` of `total`, given its depth `z`
+/// (-1 = back of the orbit, 1 = front) and the animated time in seconds
+type SchemeFn = fn(usize, usize, f64, f64) -> String;
+
+/// Deep red at the back to yellow at the front, with a slight flicker
+fn scheme_fire(index: usize, _total: usize, z: f64, time: f64) -> String {
+    let depth = z * 0.5 + 0.5;
+    let flicker = (time * 6.0 + index as f64 * 1.7).sin() * 4.0;
+    hsv_color(55.0 * depth + flicker, 1.0, 0.6 + 0.4 * depth)
+}
+
+/// Cyan at the back fading to white at the front
+fn scheme_ice(_index: usize, _total: usize, z: f64, _time: f64) -> String {
+    let depth = z * 0.5 + 0.5;
+    hsv_color(190.0, 0.8 * (1.0 - depth), 0.85 + 0.15 * depth)
+}
+
+/// Saturated green, cyan and magenta in turn, gently pulsing
+fn scheme_neon(index: usize, _total: usize, _z: f64, time: f64) -> String {
+    const HUES: [f64; 3] = [120.0, 180.0, 300.0];
+    let pulse = (time * 3.0 + index as f64).sin() * 0.5 + 0.5;
+    hsv_color(HUES[index % HUES.len()], 1.0, 0.8 + 0.2 * pulse)
+}
+
+fn scheme_monochrome(_index: usize, _total: usize, _z: f64, _time: f64) -> String {
+    "rgb(255,255,255)".to_string()
+}
+
+/// The rainbow at low saturation
+fn scheme_pastel(index: usize, total: usize, _z: f64, _time: f64) -> String {
+    hsv_color(hue_for_index(index, total), 0.35, 1.0)
+}
+
+/// Browns through to greens along the text
+fn scheme_earth(index: usize, total: usize, _z: f64, _time: f64) -> String {
+    let t = index as f64 / total.max(1) as f64;
+    hsv_color(25.0 + 85.0 * t, 0.65, 0.45 + 0.25 * t)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ColorScheme {
+    /// Index hues, cycled and depth-blended by the hue settings
+    Rainbow,
+    Fire,
+    Ice,
+    Neon,
+    Monochrome,
+    Pastel,
+    Earth,
+}
+
+impl ColorScheme {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rainbow" => Some(ColorScheme::Rainbow),
+            "fire" => Some(ColorScheme::Fire),
+            "ice" => Some(ColorScheme::Ice),
+            "neon" => Some(ColorScheme::Neon),
+            "monochrome" => Some(ColorScheme::Monochrome),
+            "pastel" => Some(ColorScheme::Pastel),
+            "earth" => Some(ColorScheme::Earth),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ColorScheme::Rainbow => "rainbow",
+            ColorScheme::Fire => "fire",
+            ColorScheme::Ice => "ice",
+            ColorScheme::Neon => "neon",
+            ColorScheme::Monochrome => "monochrome",
+            ColorScheme::Pastel => "pastel",
+            ColorScheme::Earth => "earth",
+        }
+    }
+
+    /// Color function of the scheme (None for the rainbow, which keeps the
+    /// hue-based coloring)
+    fn function(self) -> Option<SchemeFn> {
+        match self {
+            ColorScheme::Rainbow => None,
+            ColorScheme::Fire => Some(scheme_fire),
+            ColorScheme::Ice => Some(scheme_ice),
+            ColorScheme::Neon => Some(scheme_neon),
+            ColorScheme::Monochrome => Some(scheme_monochrome),
+            ColorScheme::Pastel => Some(scheme_pastel),
+            ColorScheme::Earth => Some(scheme_earth),
+        }
+    }
+
+    /// Whether colors change with depth or time and need updating every frame
+    fn is_animated(self) -> bool {
+        matches!(
+            self,
+            ColorScheme::Fire | ColorScheme::Ice | ColorScheme::Neon
+        )
+    }
+}
+
+// ============================================================================
+// Font Utilities
+// ============================================================================
+
+/// Turn a comma-separated font list into a CSS `font-family` value: names
+/// containing spaces are double-quoted, and `"` / `<` are escaped so the
+/// value can't break out of the attribute
+fn css_font_family(family: &str) -> String {
+    family
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let name = name.trim_matches(|c| c == '"' || c == '\'');
+            let escaped = name
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('<', "\\3c ");
+            if name.contains(char::is_whitespace) {
+                format!("\"{}\"", escaped)
+            } else {
+                escaped
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// True for the CSS keywords `normal`, `bold`, `bolder`, `lighter` or a
+/// numeric weight from 100 to 900 in steps of 100
+fn is_valid_font_weight(weight: &str) -> bool {
+    match weight {
+        "normal" | "bold" | "bolder" | "lighter" => true,
+        _ => weight
+            .parse::<u32>()
+            .is_ok_and(|w| (100..=900).contains(&w) && w % 100 == 0),
+    }
+}
+
+/// Drop shadow behind every character
+struct TextShadow {
+    dx: f64, // px
+    dy: f64,
+    blur: f64,     // px
+    color: String, // CSS `rgba(...)`
+}
+
+/// Normalize a shadow color — `#rgb`, `#rrggbb`, `rgb(r,g,b)` or
+/// `rgba(r,g,b,a)` — to `rgba(r,g,b,a)`, or None if it isn't one of those
+fn css_shadow_color(color: &str) -> Option<String> {
+    let color = color.trim();
+    let (r, g, b, a) = if let Some((r, g, b)) = hex_to_rgb(color) {
+        (r * 255.0, g * 255.0, b * 255.0, 1.0)
+    } else {
+        let args = color
+            .strip_prefix("rgba(")
+            .or_else(|| color.strip_prefix("rgb("))?
+            .strip_suffix(')')?;
+        let channels: Vec<f64> = args
+            .split(',')
+            .map(|c| c.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        match channels[..] {
+            [r, g, b] => (r, g, b, 1.0),
+            [r, g, b, a] => (r, g, b, a),
+            _ => return None,
+        }
+    };
+    let channel = |c: f64| c.round().clamp(0.0, 255.0) as u8;
+    Some(format!(
+        "rgba({},{},{},{})",
+        channel(r),
+        channel(g),
+        channel(b),
+        a.clamp(0.0, 1.0)
+    ))
+}
+
+// ============================================================================
+// Text Segmentation
+// ============================================================================
+
+/// Split text into the clusters a reader sees as single characters, so
+/// multi-codepoint emoji (e.g. ZWJ families) orbit as one. Whitespace is
+/// dropped, as the orbit has no gaps.
+fn grapheme_clusters(text: &str) -> Vec<String> {
+    text.graphemes(true)
+        .filter(|cluster| !cluster.chars().all(char::is_whitespace))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Split `text` into lines of at most `max_chars_per_ring` grapheme
+/// clusters (spaces included), breaking between words where possible.
+/// Words too long for a line are split across lines, each broken piece
+/// ending in a hyphen.
+fn wrap_text(text: &str, max_chars_per_ring: usize) -> Vec<String> {
+    let max = max_chars_per_ring.max(1);
+    // With room for a single character there is no space left for the hyphen
+    let piece = if max > 1 { max - 1 } else { 1 };
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_len = 0;
+
+    for word in text.split_whitespace() {
+        let clusters: Vec<&str> = word.graphemes(true).collect();
+        if line_len > 0 && line_len + 1 + clusters.len() <= max {
+            line.push(' ');
+            line.push_str(word);
+            line_len += 1 + clusters.len();
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+
+        let mut rest = clusters.as_slice();
+        while rest.len() > max {
+            let (head, tail) = rest.split_at(piece);
+            let mut broken = head.concat();
+            if max > 1 {
+                broken.push('-');
+            }
+            lines.push(broken);
+            rest = tail;
+        }
+        line = rest.concat();
+        line_len = rest.len();
+    }
+    if line_len > 0 {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Relative orbit space taken by a cluster: 1.0 for ASCII and other
+/// narrow characters, 2.0 for full-width ones such as CJK ideographs
+/// (U+4E00–U+9FFF and the other East Asian Wide blocks) and emoji.
+/// A cluster never counts as more than one wide character, however many
+/// codepoints it joins.
+fn width_factor(cluster: &str) -> f64 {
+    if cluster.is_ascii() || cluster.width() < 2 {
+        1.0
+    } else {
+        2.0
+    }
+}
+
+/// True if the first strongly-directional character is right-to-left
+/// (bidi class R or AL, e.g. Hebrew or Arabic)
+fn detect_rtl(text: &str) -> bool {
+    text.chars()
+        .find_map(|c| match bidi_class(c) {
+            BidiClass::L => Some(false),
+            BidiClass::R | BidiClass::AL => Some(true),
+            _ => None,
+        })
+        .unwrap_or(false)
+}
+
+/// Reading direction of orbit text
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextDirection {
+    /// Detect from the first strongly-directional character
+    Auto = 0,
+    Ltr = 1,
+    Rtl = 2,
+}
+
+/// Case change applied to text before it is split into characters
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextTransform {
+    None = 0,
+    Uppercase = 1,
+    Lowercase = 2,
+    /// First letter of each space-separated word capitalized, the rest lowercase
+    TitleCase = 3,
+    /// Letters alternate lower and upper case, starting lower ("hElLo")
+    AlternatingCase = 4,
+}
+
+impl TextTransform {
+    fn from_mode(mode: u8) -> Option<Self> {
+        match mode {
+            m if m == TextTransform::None as u8 => Some(TextTransform::None),
+            m if m == TextTransform::Uppercase as u8 => Some(TextTransform::Uppercase),
+            m if m == TextTransform::Lowercase as u8 => Some(TextTransform::Lowercase),
+            m if m == TextTransform::TitleCase as u8 => Some(TextTransform::TitleCase),
+            m if m == TextTransform::AlternatingCase as u8 => Some(TextTransform::AlternatingCase),
+            _ => None,
+        }
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            TextTransform::None => text.to_string(),
+            TextTransform::Uppercase => text.to_uppercase(),
+            TextTransform::Lowercase => text.to_lowercase(),
+            TextTransform::TitleCase => text
+                .split(' ')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first
+                            .to_uppercase()
+                            .chain(chars.flat_map(char::to_lowercase))
+                            .collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" "),
+            TextTransform::AlternatingCase => {
+                let mut upper = false;
+                let mut result = String::with_capacity(text.len());
+                for c in text.chars() {
+                    if !c.is_alphabetic() {
+                        result.push(c);
+                    } else if upper {
+                        result.extend(c.to_uppercase());
+                        upper = false;
+                    } else {
+                        result.extend(c.to_lowercase());
+                        upper = true;
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Depth Opacity
+// ============================================================================
+
+/// Selector for `set_opacity_curve`
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq)]
+pub enum OpacityCurveMode {
+    Constant = 0,
+    Linear = 1,
+    Cosine = 2,
+    Squared = 3,
+    /// Opaque in front of a depth threshold, invisible behind it
+    Step = 4,
+}
+
+/// How character opacity falls off from the front of the orbit to the back
+#[derive(Clone, Copy, PartialEq)]
+enum OpacityCurve {
+    Constant,
+    Linear,
+    Cosine,
+    Squared,
+    Step(f64), // Depth (-1 back to 1 front) above which characters are shown
+}
+
+impl OpacityCurve {
+    /// Curve for an `OpacityCurveMode` discriminant; `param` is the Step
+    /// threshold and ignored by the other curves
+    fn from_mode(mode: u8, param: f64) -> Option<Self> {
+        match mode {
+            m if m == OpacityCurveMode::Constant as u8 => Some(OpacityCurve::Constant),
+            m if m == OpacityCurveMode::Linear as u8 => Some(OpacityCurve::Linear),
+            m if m == OpacityCurveMode::Cosine as u8 => Some(OpacityCurve::Cosine),
+            m if m == OpacityCurveMode::Squared as u8 => Some(OpacityCurve::Squared),
+            m if m == OpacityCurveMode::Step as u8 => Some(OpacityCurve::Step(param)),
+            _ => None,
+        }
+    }
+
+    /// Opacity at `depth` (z / R: 1 = front, -1 = back). Continuous curves
+    /// stop at `floor`; Step is all or nothing.
+    fn opacity(self, depth: f64, floor: f64) -> f64 {
+        let depth = depth.clamp(-1.0, 1.0);
+        match self {
+            OpacityCurve::Constant => 1.0,
+            OpacityCurve::Linear => (depth * 0.5 + 0.5).max(floor),
+            // Quarter cosine from the front (1) to the back (0)
+            OpacityCurve::Cosine => ((1.0 - depth) / 2.0 * PI / 2.0).cos().max(floor),
+            OpacityCurve::Squared => ((depth + 1.0) / 2.0).powi(2).max(floor),
+            OpacityCurve::Step(threshold) => {
+                if depth > threshold {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Trig Lookup Table
+// ============================================================================
+
+const TRIG_TABLE_SIZE: usize = 4096; // ~0.09° resolution
+
+/// Precomputed (sin, cos) pairs so the per-frame orbit math avoids
+/// transcendental calls
+struct TrigTable {
+    entries: Box<[(f32, f32); TRIG_TABLE_SIZE]>,
+}
+
+impl TrigTable {
+    fn new() -> Self {
+        let entries: Vec<(f32, f32)> = (0..TRIG_TABLE_SIZE)
+            .map(|i| {
+                let angle = i as f64 / TRIG_TABLE_SIZE as f64 * TAU;
+                (angle.sin() as f32, angle.cos() as f32)
+            })
+            .collect();
+
+        TrigTable {
+            // Built on the heap to avoid a large stack temporary
+            entries: entries
+                .into_boxed_slice()
+                .try_into()
+                .expect("trig table has TRIG_TABLE_SIZE entries"),
+        }
+    }
+
+    /// (sin, cos) of `angle`, rounded to the nearest table entry
+    fn sin_cos(&self, angle: f64) -> (f64, f64) {
+        // The size is a power of two, so masking wraps negative and
+        // out-of-range angles without a float modulo
+        let index = (angle * (TRIG_TABLE_SIZE as f64 / TAU)).round() as i64 as usize
+            & (TRIG_TABLE_SIZE - 1);
+        let (sin, cos) = self.entries[index];
+        (sin as f64, cos as f64)
+    }
+}
+
+// ============================================================================
+// Random Numbers
+// ============================================================================
+
+const RNG_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// Small seedable xorshift64 PRNG so effects are reproducible
+struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        XorShift64 {
+            state: if seed == 0 { RNG_SEED } else { seed },
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Uniform value in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform index in [0, n)
+    fn next_index(&mut self, n: usize) -> usize {
+        (self.next_f64() * n as f64) as usize % n.max(1)
+    }
+}
+
+// ============================================================================
+// Reveal Animation
+// ============================================================================
+
+enum RevealState {
+    Idle,
+    Revealing { next_char: usize, elapsed: f64 },
+    Complete,
+}
+
+#[derive(Clone, Copy)]
+enum RevealOrder {
+    Sequential,
+    Reverse,
+    Random,
+    CenterOut,
+}
+
+impl RevealOrder {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(RevealOrder::Sequential),
+            1 => Some(RevealOrder::Reverse),
+            2 => Some(RevealOrder::Random),
+            3 => Some(RevealOrder::CenterOut),
+            _ => None,
+        }
+    }
+
+    /// Character indices in the order they should appear
+    fn sequence(self, count: usize, rng: &mut XorShift64) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..count).collect();
+        match self {
+            RevealOrder::Sequential => {}
+            RevealOrder::Reverse => order.reverse(),
+            RevealOrder::Random => {
+                // Fisher-Yates shuffle
+                for i in (1..count).rev() {
+                    order.swap(i, rng.next_index(i + 1));
+                }
+            }
+            RevealOrder::CenterOut => {
+                let middle = (count as f64 - 1.0) / 2.0;
+                order.sort_by(|a, b| {
+                    (*a as f64 - middle)
+                        .abs()
+                        .total_cmp(&(*b as f64 - middle).abs())
+                });
+            }
+        }
+        order
+    }
+}
+
+// ============================================================================
+// Warp Animation
+// ============================================================================
+
+/// Phases of the warp burst; each holds the `elapsed` time it started at
+enum WarpState {
+    Idle,
+    Accelerating(f64),
+    Scattering(f64),
+    Resetting(f64),
+}
+
+// ============================================================================
+// Easing
+// ============================================================================
+
+/// Easing curves for timed transitions. Each maps progress `t` in `[0, 1]`
+/// to eased progress, with `f(0) = 0` and `f(1) = 1`; elastic and bounce
+/// curves leave `[0, 1]` on the way.
+pub mod easing {
+    use std::f64::consts::TAU;
+
+    pub fn linear(t: f64) -> f64 {
+        t
+    }
+
+    pub fn ease_in_quad(t: f64) -> f64 {
+        t * t
+    }
+
+    pub fn ease_out_quad(t: f64) -> f64 {
+        1.0 - (1.0 - t) * (1.0 - t)
+    }
+
+    pub fn ease_in_out_quad(t: f64) -> f64 {
+        if t < 0.5 {
+            2.0 * t * t
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+        }
+    }
+
+    pub fn ease_in_cubic(t: f64) -> f64 {
+        t.powi(3)
+    }
+
+    pub fn ease_out_cubic(t: f64) -> f64 {
+        1.0 - (1.0 - t).powi(3)
+    }
+
+    pub fn ease_in_out_cubic(t: f64) -> f64 {
+        if t < 0.5 {
+            4.0 * t.powi(3)
+        } else {
+            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+        }
+    }
+
+    /// Overshoots and oscillates into place. `amplitude` (at least 1) is the
+    /// size of the first swing, `period` the length of an oscillation in `t`.
+    pub fn ease_out_elastic(t: f64, amplitude: f64, period: f64) -> f64 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= 1.0 {
+            return 1.0;
+        }
+        let amplitude = amplitude.max(1.0);
+        let shift = period / TAU * (1.0 / amplitude).asin();
+        amplitude * 2f64.powf(-10.0 * t) * ((t - shift) * TAU / period).sin() + 1.0
+    }
+
+    /// Falls into place like a ball bouncing to rest
+    pub fn ease_out_bounce(t: f64) -> f64 {
+        const N: f64 = 7.5625;
+        const D: f64 = 2.75;
+        if t < 1.0 / D {
+            N * t * t
+        } else if t < 2.0 / D {
+            let t = t - 1.5 / D;
+            N * t * t + 0.75
+        } else if t < 2.5 / D {
+            let t = t - 2.25 / D;
+            N * t * t + 0.9375
+        } else {
+            let t = t - 2.625 / D;
+            N * t * t + 0.984375
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Curve = (&'static str, fn(f64) -> f64);
+
+        const CURVES: [Curve; 7] = [
+            ("linear", linear),
+            ("ease_in_quad", ease_in_quad),
+            ("ease_out_quad", ease_out_quad),
+            ("ease_in_out_quad", ease_in_out_quad),
+            ("ease_in_cubic", ease_in_cubic),
+            ("ease_out_cubic", ease_out_cubic),
+            ("ease_in_out_cubic", ease_in_out_cubic),
+        ];
+
+        fn elastic(t: f64) -> f64 {
+            ease_out_elastic(t, 1.0, 0.3)
+        }
+
+        /// `t` from 0 to 1 inclusive in `steps` steps
+        fn samples(steps: u32) -> impl Iterator<Item = f64> {
+            (0..=steps).map(move |i| i as f64 / steps as f64)
+        }
+
+        #[test]
+        fn every_curve_starts_at_0_and_ends_at_1() {
+            let others: [Curve; 2] = [
+                ("ease_out_elastic", elastic),
+                ("ease_out_bounce", ease_out_bounce),
+            ];
+            for (name, curve) in CURVES.into_iter().chain(others) {
+                assert!(curve(0.0).abs() < 1e-9, "{}(0) = {}", name, curve(0.0));
+                assert!(
+                    (curve(1.0) - 1.0).abs() < 1e-9,
+                    "{}(1) = {}",
+                    name,
+                    curve(1.0)
+                );
+            }
+        }
+
+        #[test]
+        fn polynomial_curves_never_go_back() {
+            for (name, curve) in CURVES {
+                let values: Vec<f64> = samples(1000).map(curve).collect();
+                for (i, pair) in values.windows(2).enumerate() {
+                    assert!(pair[1] >= pair[0], "{} decreases after step {}", name, i);
+                }
+            }
+        }
+
+        #[test]
+        fn bounce_stays_within_the_range() {
+            for t in samples(1000) {
+                let value = ease_out_bounce(t);
+                assert!(
+                    (0.0..=1.0 + 1e-9).contains(&value),
+                    "bounce({}) = {}",
+                    t,
+                    value
+                );
+            }
+        }
+
+        #[test]
+        fn elastic_overshoots_but_settles() {
+            let values: Vec<f64> = samples(1000).map(elastic).collect();
+            assert!(values.iter().any(|&v| v > 1.0));
+            assert!(values[900..].iter().all(|v| (v - 1.0).abs() < 0.01));
+        }
+    }
+}
+
+// ============================================================================
+// Spin Transitions
+// ============================================================================
+
+/// Easing curve of a timed transition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EaseMode {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    Spring, // Overshoots and settles
+}
+
+impl EaseMode {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(EaseMode::Linear),
+            "ease-in" => Some(EaseMode::EaseIn),
+            "ease-out" => Some(EaseMode::EaseOut),
+            "ease-in-out" => Some(EaseMode::EaseInOut),
+            "spring" => Some(EaseMode::Spring),
+            _ => None,
+        }
+    }
+
+    /// Eased progress for linear progress `t` in `[0, 1]` (cubic curves)
+    fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            EaseMode::Linear => easing::linear(t),
+            EaseMode::EaseIn => easing::ease_in_cubic(t),
+            EaseMode::EaseOut => easing::ease_out_cubic(t),
+            EaseMode::EaseInOut => easing::ease_in_out_cubic(t),
+            EaseMode::Spring => easing::ease_out_elastic(t, 1.0, SPRING_EASE_PERIOD),
+        }
+    }
+}
+
+/// Primary-ring speed change started by `spin_up`
+struct SpinTransition {
+    from: f64, // rad/s
+    to: f64,   // rad/s
+    elapsed: f64,
+    duration: f64, // Seconds
+}
+
+// ============================================================================
+// Scramble Animation
+// ============================================================================
+
+/// Primary-ring characters show random glyphs until their resolve time
+struct ScrambleState {
+    elapsed: f64,
+    resolve_at: Vec<f64>, // Seconds until each character settles
+    resolved: Vec<bool>,
+}
+
+/// Random printable ASCII character (excluding space)
+fn random_printable(rng: &mut XorShift64) -> char {
+    (b'!' + rng.next_index((b'~' - b'!' + 1) as usize) as u8) as char
+}
+
+// ============================================================================
+// Morph Transition
+// ============================================================================
+
+/// Cross-fade of the primary ring from its previous text to the current one
+struct MorphState {
+    old_chars: Vec<Character>, // Previous text's characters, fading out in place
+    progress: f64,             // 0 = old text fully shown, 1 = new text fully shown
+    duration: f64,             // Seconds
+}
+
+// ============================================================================
+// Character Data Structures
+// ============================================================================
+
+/// Per-frame render data for one character
+struct CharRenderData {
+    ring: usize,  // Index into TextSphere::rings
+    index: usize, // Index into OrbitRing::characters
+    screen_x: f64,
+    screen_y: f64,
+    font_size: f64,
+    opacity: f64,
+    z: f64,
+    scale: f64, // Perspective scale (1.0 at the orbit center's depth)
+    scale_x: f64,
+    skew: f64,
+    normal: (f64, f64, f64), // Outward unit normal (x right, y up, z towards the viewer)
+}
+
+/// Recorded render state of a character for its particle trail
+#[derive(Clone, Copy)]
+struct TrailPoint {
+    screen_x: f64,
+    screen_y: f64,
+    font_size: f64,
+    opacity: f64,
+    z: f64,
+    scale_x: f64,
+    skew: f64,
+}
+
+struct Character {
+    element: CharHandle,
+    glyph: String,                // Text shown by the element once any effects settle
+    base_angle: f64,              // Position along orbit (0-2π)
+    base_hue: f64,                // Hue assigned from the character's index (degrees)
+    glow_filter: Option<JsValue>, // Per-character glow <filter>, created while glow is on
+    offset_x: f64,                // Visual displacement from the orbit position (px)
+    offset_y: f64,
+    vel_x: f64, // Velocity of the displacement (px/s)
+    vel_y: f64,
+    highlighted: bool,
+    highlight_scale: f64, // Current size multiplier, springing towards HIGHLIGHT_SCALE or 1.0
+    highlight_vel: f64,
+    trail: VecDeque<TrailPoint>, // Previous frames' render state, newest first
+    frozen: Option<f64>,         // Orbit angle the character is pinned to, if any
+    ghosts: Vec<CharHandle>,     // Pooled clones drawn at the trail positions
+    width_factor: f64,           // Relative orbit space taken (2.0 for wide characters)
+    custom_color: Option<String>, // Fill overriding the derived color
+    ticker_travel: f64,          // Orbit angle covered since entering the ticker (radians)
+    orbit_offset: f64,           // Shift along the orbit from the laid-out position (radians)
+    spring_to_base: Option<SpringToBase>, // Springing back after randomize_positions
+}
+
+/// A character's base angle springing back to its laid-out value
+struct SpringToBase {
+    target_angle: f64, // Laid-out base angle
+    spring_k: f64,     // Stiffness; damping is √k (damping ratio 0.5)
+    vel: f64,          // rad/s
+}
+
+impl SpringToBase {
+    /// Advance by `dt` seconds from `offset` radians away from the target,
+    /// returning the new offset. Stiff springs (short durations) take
+    /// several sub-steps so the integration stays stable.
+    fn step(&mut self, offset: f64, dt: f64) -> f64 {
+        let damping = self.spring_k.sqrt();
+        let steps = (damping * dt / MAX_SPRING_PHASE_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        let mut offset = offset;
+        for _ in 0..steps as u32 {
+            self.vel += (-self.spring_k * offset - damping * self.vel) * h;
+            offset += self.vel * h;
+        }
+        offset
+    }
+}
+
+// ============================================================================
+// Orbit Shapes
+// ============================================================================
+
+#[derive(Clone, Copy)]
+struct LissajousParams {
+    a: f64,     // Frequency along x
+    b: f64,     // Frequency along z
+    delta: f64, // Phase offset of x (radians)
+}
+
+#[derive(Clone, Copy)]
+enum OrbitShape {
+    /// Circle, or ellipse when the ring's x and z radii differ
+    Circle,
+    /// Lissajous figure: a = 1, b = 2 traces a figure-eight
+    Lissajous(LissajousParams),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CursorMode {
+    None,
+    Attract,
+    Repel,
+}
+
+impl CursorMode {
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(CursorMode::None),
+            1 => Some(CursorMode::Attract),
+            2 => Some(CursorMode::Repel),
+            _ => None,
+        }
+    }
+}
+
+// ============================================================================
+// OrbitRing - One ring of characters orbiting the sphere
+// ============================================================================
+
+struct OrbitRing {
+    id: u32,      // Handle given out by add_ring; unlike the index, it survives ring removal
+    text: String, // Text as displayed (after the sphere's text transform), including spaces
+    source_text: String, // Text as last set, before the text transform
+    characters: Vec<Character>,
+    orbit_radius_x: f64, // Horizontal radius of the orbit ellipse
+    orbit_radius_z: f64, // Depth radius of the orbit ellipse (equal to x for a circle)
+    rotation_speed: f64, // rad/s (negative = reverse direction)
+    current_angle: f64,
+    shape: OrbitShape,
+    helix_height: f64, // Vertical extent of the string's corkscrew (0 = flat ring)
+    inclination: f64,  // Tilt of the orbit plane around the X axis (radians)
+    tilt_target: f64,  // Inclination being animated towards
+    tilt_speed: f64,   // rad/s
+    letter_spacing: f64, // Scale of the angular gap between characters (1.0 = evenly spread)
+    start_angle: f64,  // Orbit angle the text is centered on at time zero (π/2 = front)
+    rtl: bool,         // Right-to-left text: characters run the other way round the orbit
+    radius_target: f64, // Horizontal radius being animated towards
+    radius_from: f64,  // Horizontal radius the animation started at
+    radius_progress: f64, // Linear progress of the radius animation (1 = done)
+    radius_speed: f64, // px/s
+    speed_target: f64, // Rotation speed being eased towards (rad/s)
+    speed_acceleration: f64, // rad/s²
+}
+
+impl OrbitRing {
+    fn new(
+        renderer: &dyn Renderer,
+        text: &str,
+        orbit_radius: f64,
+        rotation_speed: f64,
+        center_x: f64,
+        center_y: f64,
+    ) -> Self {
+        OrbitRing {
+            id: 0,
+            text: text.to_string(),
+            source_text: text.to_string(),
+            characters: Self::create_characters(
+                renderer,
+                text,
+                orbit_radius,
+                orbit_radius,
+                center_x,
+                center_y,
+                1.0,
+            ),
+            orbit_radius_x: orbit_radius,
+            orbit_radius_z: orbit_radius,
+            rotation_speed,
+            current_angle: 0.0,
+            shape: OrbitShape::Circle,
+            helix_height: 0.0,
+            inclination: 0.0,
+            tilt_target: 0.0,
+            tilt_speed: TILT_SPEED,
+            letter_spacing: 1.0,
+            start_angle: PI / 2.0,
+            rtl: false,
+            radius_target: orbit_radius,
+            radius_from: orbit_radius,
+            radius_progress: 1.0,
+            radius_speed: RADIUS_SPEED,
+            speed_target: rotation_speed,
+            speed_acceleration: SPEED_ACCELERATION,
+        }
+    }
+
+    /// Point on the orbit at `angle` as `(x, z, dx/dθ, dz/dθ)`
+    fn orbit_point(&self, trig: &TrigTable, angle: f64) -> (f64, f64, f64, f64) {
+        let (rx, rz) = (self.orbit_radius_x, self.orbit_radius_z);
+        match self.shape {
+            OrbitShape::Circle => {
+                let (sin, cos) = trig.sin_cos(angle);
+                (rx * cos, rz * sin, -rx * sin, rz * cos)
+            }
+            OrbitShape::Lissajous(LissajousParams { a, b, delta }) => {
+                let (sin_a, cos_a) = trig.sin_cos(a * angle + delta);
+                let (sin_b, cos_b) = trig.sin_cos(b * angle);
+                (rx * sin_a, rz * sin_b, rx * a * cos_a, rz * b * cos_b)
+            }
+        }
+    }
+
+    /// Positions along the orbit for characters of the given widths. Each
+    /// character's share of the orbit is proportional to its width, scaled by
+    /// `spacing`, and the group stays centered on `start` whatever the spacing.
+    /// A single character is placed at `start`.
+    fn base_angles(widths: &[f64], spacing: f64, start: f64) -> Vec<f64> {
+        if widths.len() == 1 {
+            return vec![start];
+        }
+        let total: f64 = widths.iter().sum();
+        let unit = (2.0 * PI / total) * spacing;
+        let mut preceding = 0.0;
+        widths
+            .iter()
+            .map(|width| {
+                let center = preceding + width / 2.0;
+                preceding += width;
+                start + (total / 2.0 - center) * unit
+            })
+            .collect()
+    }
+
+    /// Change the spacing between characters (clamped to 0–10), moving the
+    /// existing elements
+    fn set_letter_spacing(&mut self, spacing: f64) {
+        self.letter_spacing = spacing.clamp(0.0, MAX_LETTER_SPACING);
+        self.layout();
+    }
+
+    /// Move the text to be centered on `angle` (wrapped to `[0, 2π)`) at
+    /// time zero, moving the existing elements
+    fn set_start_angle(&mut self, angle: f64) {
+        self.start_angle = angle.rem_euclid(TAU);
+        self.layout();
+    }
+
+    /// Recompute every character's base angle from its width, the letter
+    /// spacing, the start angle and the reading direction
+    fn layout(&mut self) {
+        let mut widths: Vec<f64> = self.characters.iter().map(|c| c.width_factor).collect();
+        if self.rtl {
+            widths.reverse();
+        }
+        let mut angles = Self::base_angles(&widths, self.letter_spacing, self.start_angle);
+        // Right-to-left text runs the other way round: the last character
+        // gets the smallest angle
+        if self.rtl {
+            angles.reverse();
+        }
+        for (character, angle) in self.characters.iter_mut().zip(angles) {
+            match &mut character.spring_to_base {
+                Some(spring) => spring.target_angle = angle,
+                None => character.base_angle = angle,
+            }
+        }
+    }
+
+    /// Switch reading direction by mirroring the layout. The rotation speed
+    /// is left alone: it belongs to the user (and to effects that save and
+    /// restore it), not to the text.
+    fn set_rtl(&mut self, rtl: bool) {
+        if rtl == self.rtl {
+            return;
+        }
+        self.rtl = rtl;
+        self.layout();
+    }
+
+    fn create_characters(
+        renderer: &dyn Renderer,
+        text: &str,
+        orbit_radius_x: f64,
+        orbit_radius_z: f64,
+        center_x: f64,
+        center_y: f64,
+        spacing: f64,
+    ) -> Vec<Character> {
+        let clusters = grapheme_clusters(text);
+        let char_count = clusters.len();
+        let widths: Vec<f64> = clusters.iter().map(|c| width_factor(c)).collect();
+        let angles = Self::base_angles(&widths, spacing, PI / 2.0);
+        let mut characters = Vec::with_capacity(char_count);
+
+        for (i, cluster) in clusters.into_iter().enumerate() {
+            let base_angle = angles[i];
+            let base_hue = hue_for_index(i, char_count);
+            let color = get_color_for_hue(base_hue, 1.0);
+
+            // Calculate initial position (at base_angle)
+            let x = orbit_radius_x * base_angle.cos();
+            let z = orbit_radius_z * base_angle.sin();
+
+            // Project to 2D
+            let scale = PERSPECTIVE_DISTANCE / (PERSPECTIVE_DISTANCE + z);
+            let placement = Placement {
+                x: center_x + x,
+                y: center_y,
+                font_size: LETTER_SIZE * scale,
+                opacity: 1.0,
+                scale_x: 1.0,
+                skew: 0.0,
+            };
+
+            let element = renderer.create_character(&cluster, &color, &placement);
+
+            characters.push(Character {
+                element,
+                glyph: cluster,
+                base_angle,
+                base_hue,
+                glow_filter: None,
+                offset_x: 0.0,
+                offset_y: 0.0,
+                vel_x: 0.0,
+                vel_y: 0.0,
+                highlighted: false,
+                highlight_scale: 1.0,
+                highlight_vel: 0.0,
+                trail: VecDeque::new(),
+                frozen: None,
+                width_factor: widths[i],
+                custom_color: None,
+                ticker_travel: 0.0,
+                orbit_offset: 0.0,
+                spring_to_base: None,
+                ghosts: Vec::new(),
+            });
+        }
+
+        characters
+    }
+
+    /// Replace the ring's characters with `text`.
+    /// Existing handles are reused when the character count is unchanged;
+    /// otherwise they are removed and recreated. Either way the characters
+    /// are laid out again; positions are applied on the next animation frame.
+    fn set_text(&mut self, renderer: &dyn Renderer, text: &str, center_x: f64, center_y: f64) {
+        self.text = text.to_string();
+        let clusters = grapheme_clusters(text);
+
+        if clusters.len() == self.characters.len() {
+            // Same length: colors are unchanged, only swap glyphs and re-space
+            // for any change in character widths
+            for (character, cluster) in self.characters.iter_mut().zip(clusters) {
+                character.width_factor = width_factor(&cluster);
+                character.glyph = cluster;
+                renderer.set_character_text(&character.element, &character.glyph);
+                for ghost in &character.ghosts {
+                    renderer.set_character_text(ghost, &character.glyph);
+                }
+            }
+            self.layout();
+            return;
+        }
+
+        self.remove_elements(renderer);
+        self.characters = Self::create_characters(
+            renderer,
+            text,
+            self.orbit_radius_x,
+            self.orbit_radius_z,
+            center_x,
+            center_y,
+            self.letter_spacing,
+        );
+        self.layout();
+    }
+
+    /// Remove all of this ring's characters from the renderer
+    fn remove_elements(&mut self, renderer: &dyn Renderer) {
+        for character in self.characters.drain(..) {
+            Self::remove_character(renderer, &character);
+        }
+    }
+
+    /// Remove a character's element, glow filter and trail ghosts
+    fn remove_character(renderer: &dyn Renderer, character: &Character) {
+        renderer.remove(&character.element);
+        if let Some(filter) = &character.glow_filter {
+            remove_element(filter);
+        }
+        for ghost in &character.ghosts {
+            renderer.remove(ghost);
+        }
+    }
+
+    /// Rotate by one frame; returns true when the angle wraps past 2π (or 0)
+    fn advance(&mut self, delta: f64) -> bool {
+        let angle = self.current_angle + self.rotation_speed * delta;
+
+        // Keep angle in reasonable range (speed may be negative)
+        self.current_angle = angle.rem_euclid(2.0 * PI);
+        !(0.0..2.0 * PI).contains(&angle)
+    }
+
+    /// Step the inclination towards `tilt_target` without overshooting
+    fn update_tilt(&mut self, delta: f64) {
+        let remaining = self.tilt_target - self.inclination;
+        if remaining.abs() > 0.001 {
+            let step = (self.tilt_speed * delta).min(remaining.abs());
+            self.inclination += step.copysign(remaining);
+        }
+    }
+
+    /// Ease the rotation speed towards `speed_target`, so a reversal slows,
+    /// stops and spins back up rather than jumping
+    fn update_speed(&mut self, delta: f64) {
+        let remaining = self.speed_target - self.rotation_speed;
+        if remaining.abs() > 0.0001 {
+            let step = (self.speed_acceleration * delta).min(remaining.abs());
+            self.rotation_speed += step.copysign(remaining);
+        } else {
+            self.rotation_speed = self.speed_target;
+        }
+    }
+
+    /// Set the orbit radii immediately, cancelling any radius animation
+    fn set_radii(&mut self, radius_x: f64, radius_z: f64) {
+        self.orbit_radius_x = radius_x;
+        self.orbit_radius_z = radius_z;
+        self.radius_target = radius_x;
+        self.radius_progress = 1.0;
+    }
+
+    /// Start animating the horizontal radius towards `radius`
+    fn set_radius_target(&mut self, radius: f64) {
+        self.radius_target = radius;
+        self.radius_from = self.orbit_radius_x;
+        self.radius_progress = 0.0;
+    }
+
+    /// Step the horizontal radius towards its target, easing in and out at
+    /// an average of `radius_speed`. The depth radius scales with it so an
+    /// ellipse keeps its proportions.
+    fn update_radius(&mut self, delta: f64) {
+        if self.radius_progress < 1.0 {
+            let span = self.radius_target - self.radius_from;
+            self.radius_progress = if span.abs() > 0.01 {
+                (self.radius_progress + self.radius_speed * delta / span.abs()).min(1.0)
+            } else {
+                1.0
+            };
+            let radius_x =
+                self.radius_from + span * easing::ease_in_out_cubic(self.radius_progress);
+            if self.orbit_radius_x != 0.0 {
+                self.orbit_radius_z *= radius_x / self.orbit_radius_x;
+            }
+            self.orbit_radius_x = radius_x;
+        }
+    }
+}
+
+/// Convert an inclination in degrees to radians in `[-π/2, π/2]`.
+/// Angles are first wrapped to `(-π, π]` so e.g. 350° tilts by -10°.
+fn inclination_from_degrees(degrees: f64) -> f64 {
+    let wrapped = PI - (PI - degrees.to_radians()).rem_euclid(2.0 * PI);
+    wrapped.clamp(-PI / 2.0, PI / 2.0)
+}
+
+// ============================================================================
+// TextSphere - Main Application Logic
+// ============================================================================
+
+/// Callback of a sphere's container `ResizeObserver`
+type ResizeCallback = Closure<dyn Fn(js_sys::Array)>;
+
+/// Pointer enter/leave listener on one character
+type HoverCallback = Closure<dyn Fn(web_sys::PointerEvent)>;
+
+struct TextSphere {
+    container: web_sys::Element, // Element the SVG (or canvas) was created in
+    renderer: Box<dyn Renderer>,
+    live_region: web_sys::HtmlElement, // aria-live copy of the text for screen readers
+    aria_label_override: Option<String>, // Host-supplied label for the surface
+    sphere: CharHandle,
+    sphere_gradient: [String; 3], // (highlight, mid, shadow) colors of the sphere
+    high_contrast: Option<ContrastRestore>, // Settings to restore once high contrast is off
+    storage_key: String,          // localStorage key for this instance's saved settings
+    listeners: Vec<Listener>,     // Page event listeners, removed when the sphere is destroyed
+    resize_observer: Option<(web_sys::ResizeObserver, ResizeCallback)>, // Watches the container
+    rings: Vec<OrbitRing>,        // rings[0] holds the primary text and is never removed
+    next_ring_id: u32,            // Id of the next ring added (the primary ring is 0)
+    trig: TrigTable,
+    perspective_distance: f64, // Camera distance; small = fisheye, large = near-orthographic
+    rng: XorShift64,
+    reveal_state: RevealState,
+    reveal_order: RevealOrder,
+    reveal_rank: Vec<usize>, // Position of each primary-ring character in the reveal sequence
+    reveal_char_delay: f64,  // Seconds between successive characters starting to fade in
+    reveal_fade_duration: f64, // Seconds for one character to fade in
+    scramble: Option<ScrambleState>,
+    morph: Option<MorphState>,
+    warp_state: WarpState,
+    warp_phase_duration: f64,              // Seconds per warp phase
+    warp_saved_speeds: Vec<f64>,           // Ring speeds from before the warp
+    pending_events: Vec<(String, String)>, // (name, detail JSON) to fire once unborrowed
+    enabled_events: Vec<&'static str>,     // Events that are fired (all off by default)
+    lap_count: u64,                        // Full turns completed by the primary ring
+    elapsed: f64,                          // Total animated time in seconds
+    pulse_amplitude: f64,                  // Fractional size change of the pulse (0 = off)
+    pulse_frequency: f64,                  // Pulses per second
+    beat_pulse: bool,                      // Sharp-attack pulse on each beat instead of a sine
+    beat_origin: f64,                      // Elapsed time (s) of a beat, to line up with taps
+    tap_times: VecDeque<f64>,              // performance.now() of recent tap_tempo calls
+    detected_bpm: f64,                     // Tempo measured by tap_tempo (0 = none yet)
+    bounce_height: f64,                    // Pixels of bounce (0 = off)
+    bounce_frequency: f64,                 // Bounces per orbit
+    light_direction: (f64, f64, f64),      // Unit vector towards the light (y up, z to viewer)
+    light_ambient: f64,                    // Brightness with no direct light (0-1)
+    light_diffuse: f64,                    // Brightness added by direct light (0-1)
+    light_color: (f64, f64, f64),          // RGB (0-1) of the direct and rim light
+    light_orbiting: bool,                  // The light circles the sphere instead of staying fixed
+    light_angle: f64,                      // Position of an orbiting light (radians, π/2 = front)
+    light_speed: f64,                      // rad/s of an orbiting light (0 = frozen)
+    light_orbit_radius: f64,               // px from the vertical axis of an orbiting light
+    wobble_amplitude: f64,                 // Radians the orbit plane rocks by (0 = off)
+    wobble_frequency: f64,                 // Rocks per second
+    mouse_x: f64, // Cursor position in SVG coordinates (NaN until the mouse moves)
+    mouse_y: f64,
+    cursor_mode: CursorMode,
+    cursor_strength: f64,  // Force = strength / distance²
+    influence_radius: f64, // Pixels beyond which the cursor has no effect
+    max_displacement: f64, // Furthest (px) the cursor can move a character
+    spring_stiffness: f64, // Spring constant returning displaced characters
+    spring_damping: f64,
+    use_ortho: bool, // Orthographic projection (no depth scaling)
+    center_x: f64,
+    center_y: f64,
+    paused: bool,
+    hidden_paused: bool,         // Paused by the page being hidden
+    blur_speed_factor: f64,      // Speed multiplier while the window is blurred
+    pre_blur_speed: Option<f64>, // Primary speed before the window was blurred
+    blurred_speed: f64,          // Slowed speed the blur eased to
+    last_time: f64,              // Timestamp of the previous frame (0 = no previous frame)
+    loop_generation: u32,        // Incremented each time the animation loop is (re)started
+    vertical_amplitude: f64,     // Pixels of vertical wave (0 = flat orbit)
+    vertical_frequency: f64,     // Wave cycles per orbit
+    depth_fade: bool,            // Fade characters as they move behind the sphere
+    min_opacity: f64,            // Opacity floor for depth fade
+    opacity_curve: OpacityCurve, // Opacity against depth while depth fade is on
+    hue_offset: f64,             // Degrees added to every character's hue
+    hue_cycle_speed: f64,        // Degrees per second (0 = static colors)
+    depth_color_blend: f64,      // 0 = index colors, 1 = colors from z-depth only
+    colors_dirty: bool,          // Recolor all characters on the next frame
+    color_scheme: ColorScheme,
+    font_family: String,      // CSS font-family value applied to every character
+    font_weight: String,      // CSS font-weight value applied to every character
+    skew_intensity: f64,      // Scale of the side-on letter skew (0 = upright letters)
+    background_color: String, // Solid background fill ("" = none)
+    highlight_color: String,  // Fill of highlighted characters
+    stroke_width: f64,        // Character outline width in px (0 = no outline)
+    text_direction: TextDirection,
+    text_transform: TextTransform, // Case change applied to every ring's text
+    trail_length: usize,           // Ghost copies per character (0 = no trail)
+    trail_decay: f64,              // Ghost opacity multiplier per frame of age
+    stroke_color: String,
+    stroke_opacity: f64,
+    text_shadow: Option<TextShadow>,
+    shadow_perspective: bool, // Scale shadow offsets by each character's perspective
+    sync_url_hash: bool,      // Mirror settings into the URL hash as they change
+    text_feed: Option<web_sys::WebSocket>,
+    text_feed_url: Option<String>, // Feed to stay connected to (None = disconnected)
+    text_feed_handlers: Vec<Box<dyn std::any::Any>>, // Callbacks of the current socket
+    text_feed_retry_timer: Option<i32>, // Pending reconnect setTimeout handle
+    text_feed_retry_delay: f64,    // Seconds before the next reconnect attempt
+    tab_sync: Option<TabSync>,     // Rotation shared with other tabs over a BroadcastChannel
+    text_loading: bool,            // A fetch_text request is in flight (characters are dimmed)
+    text_fetch_generation: u32,    // Incremented per fetch_text so only the latest one applies
+    max_clipboard_chars: usize,    // Pasted text is cut to this many characters
+    clipboard_paste_enabled: bool, // Ctrl+V / Cmd+V replaces the text with the clipboard's
+    drop_target_active: bool,      // Text is being dragged over the sphere (it is highlighted)
+    ticker_mode: bool, // Ring 0 streams characters from ticker_queue instead of its text
+    ticker_queue: VecDeque<(String, String)>, // (glyph, color) waiting to enter the ticker
+    ticker_hue: f64,   // Hue given to the next pushed ticker character
+    free_list: Vec<CharHandle>, // Hidden handles of departed ticker characters
+    letter_size: f64,  // Base font size (px) before perspective scaling
+    letter_size_target: f64, // Letter size being animated towards
+    letter_size_from: f64, // Letter size the animation started at
+    letter_size_progress: f64, // Linear progress of the size animation (1 = done)
+    letter_size_speed: f64, // px/s
+    glow_enabled: bool,
+    max_glow: f64,       // Blur std deviation (px) at the front of the orbit
+    next_filter_id: u32, // Counter for unique glow filter ids
+    sphere_radius: f64,
+    sphere_radius_target: f64,      // Sphere radius being animated towards
+    sphere_radius_from: f64,        // Sphere radius the animation started at
+    sphere_radius_progress: f64,    // Linear progress of the radius animation (1 = done)
+    sphere_radius_speed: f64,       // px/s
+    sphere_shadow: bool,            // Darken characters passing behind the sphere
+    shadow_softness: f64,           // Width (px) of the shadow's penumbra
+    frame_times: [f64; FPS_WINDOW], // Ring buffer of recent frame deltas (seconds)
+    frame_time_index: usize,        // Next slot to write in frame_times
+    frame_time_count: usize,        // Number of valid entries (until the buffer fills)
+    show_fps: bool,                 // Draw the FPS counter via the debug info text
+    adaptive_quality: bool,         // Reduce quality automatically when FPS drops
+    fps_low: f64,                   // Step quality down below this FPS
+    fps_high: f64,                  // Step quality up above this FPS
+    quality_step: usize,            // Render every Nth character (1 = all)
+    quality_letter_factor: f64,     // Letter size multiplier (1.0 = full size)
+    quality_cooldown: f64,          // Seconds until the next quality change is allowed
+    drag_active: bool,              // Mouse drag in progress (suspends autonomous rotation)
+    respect_reduced_motion: bool,   // Stay still when the OS asks for reduced motion
+    reduced_motion_speed: Option<f64>, // Speed to restore once reduced motion no longer applies
+    recording: Vec<FrameSnapshot>,  // Frames captured by start_recording
+    recording_remaining: usize,     // Frames still to capture (0 = not recording)
+    playback_generation: u32,       // Incremented per play_recording so only the latest plays
+    resume_after_playback: bool,    // Animation was running when playback paused it
+    resize_debounce_ms: u32,        // Delay before a resize sequence reflows (0 = immediately)
+    drag_last_x: f64,               // clientX of the previous drag event
+    drag_sensitivity: f64,          // Radians per pixel dragged
+    pinch_ids: Option<(i32, i32)>,  // Touch identifiers of an active two-finger pinch
+    pinch_last_distance: f64,       // Finger distance at the previous pinch event
+    gyro_enabled: bool,             // Device orientation turns and tilts the rings
+    gyro_filtered: Option<(f64, f64)>, // Low-passed (angle, inclination) from the last reading
+    spin: Option<SpinTransition>,   // Eased primary-ring speed change in progress
+    spin_ease: EaseMode,            // Curve used by spin_up
+    defer_updates: bool,            // Move SVG z-ordering and recoloring to idle time
+    deferred_updates: Vec<DeferredUpdate>, // Non-critical changes waiting for idle time
+    idle_flush_pending: bool,       // An idle callback is scheduled to flush deferred_updates
+    char_hover_callback: Option<js_sys::Function>, // Called as (index, char, is_entering)
+    char_hover_listeners: Vec<(web_sys::EventTarget, HoverCallback)>,
+    click_callback: Option<js_sys::Function>, // Called as (index, char, x, y) on character clicks
+    click_through: bool, // The surface ignores the pointer so clicks reach the page below
+    audio_context: Option<web_sys::AudioContext>, // Created on the first click (autoplay policy)
+    audio_enabled: bool,
+    char_sound_map: Vec<f64>, // Tone frequency (Hz) per primary-ring character index
+    mic_input: Option<MicInput>,
+    frequency_bands: usize, // Characters sized by this many spectrum bands (0 = off)
+    band_amplitude_factor: f64, // Size gain at a band's full level
+}
+
+impl TextSphere {
+    fn new(container_id: &str) -> Option<Self> {
+        if !check_d3_available() {
+            log::error!("d3.js is not available");
+            return None;
+        }
+
+        let window = web_sys::window()?;
+        let Some(container) = window.document()?.get_element_by_id(container_id) else {
+            log::error!("no element with id {:?} to hold the sphere", container_id);
+            return None;
+        };
+        let (width, height) = container_size(&container)?;
+
+        let center_x = width / 2.0;
+        let center_y = height / 2.0;
+
+        // Settings saved by a previous visit, applied once the scene exists
+        let storage_key = config_storage_key(container_id);
+        let saved_config = load_from_local_storage(&storage_key)
+            .as_string()
+            .and_then(|json| match parse_saved_config(&json) {
+                Ok(config) => Some(config),
+                Err(e) => {
+                    log::warn!("ignoring saved settings: {}", e);
+                    None
+                }
+            });
+
+        // Settings in the URL hash override saved ones
+        let hash_config = window.location().hash().ok().and_then(|hash| {
+            let config = parse_hash_config(&hash);
+            match config.validate() {
+                Ok(()) => Some(config),
+                Err(e) => {
+                    log::warn!("ignoring URL hash settings: {}", e);
+                    None
+                }
+            }
+        });
+
+        // Render to SVG until use_canvas_backend() swaps the renderer
+        let renderer: Box<dyn Renderer> = Box::new(SvgRenderer::new(container_id, width, height));
+        let live_region = create_live_region(&container).ok()?;
+
+        // Create central sphere (appended to svg)
+        let sphere = renderer.create_sphere(center_x, center_y, SPHERE_RADIUS);
+
+        // Create the primary ring of characters
+        let text_transform = TextTransform::None;
+        let mut primary = OrbitRing::new(
+            renderer.as_ref(),
+            &text_transform.apply(TEXT_TO_DISPLAY),
+            ORBIT_RADIUS,
+            ROTATION_SPEED,
+            center_x,
+            center_y,
+        );
+        primary.source_text = TEXT_TO_DISPLAY.to_string();
+
+        let mut text_sphere = TextSphere {
+            container,
+            renderer,
+            live_region,
+            aria_label_override: None,
+            sphere,
+            sphere_gradient: ["#6699ff", "#3366cc", "#1a3366"].map(String::from),
+            high_contrast: None,
+            storage_key,
+            listeners: Vec::new(),
+            resize_observer: None,
+            rings: vec![primary],
+            next_ring_id: 1,
+            trig: TrigTable::new(),
+            perspective_distance: PERSPECTIVE_DISTANCE,
+            rng: XorShift64::new(RNG_SEED),
+            reveal_state: RevealState::Idle,
+            reveal_order: RevealOrder::Sequential,
+            reveal_rank: Vec::new(),
+            reveal_char_delay: 0.0,
+            reveal_fade_duration: 0.0,
+            scramble: None,
+            morph: None,
+            warp_state: WarpState::Idle,
+            warp_phase_duration: 0.0,
+            warp_saved_speeds: Vec::new(),
+            pending_events: Vec::new(),
+            enabled_events: Vec::new(),
+            lap_count: 0,
+            elapsed: 0.0,
+            pulse_amplitude: 0.0,
+            pulse_frequency: 1.0,
+            beat_pulse: false,
+            beat_origin: 0.0,
+            tap_times: VecDeque::with_capacity(TAP_TEMPO_TAPS),
+            detected_bpm: 0.0,
+            bounce_height: 0.0,
+            bounce_frequency: 4.0,
+            light_direction: normalize(LIGHT_DIRECTION.0, LIGHT_DIRECTION.1, LIGHT_DIRECTION.2)
+                .unwrap_or((0.0, 0.0, 1.0)),
+            light_ambient: 1.0,
+            light_diffuse: 0.0,
+            light_color: (1.0, 1.0, 1.0),
+            light_orbiting: false,
+            light_angle: PI / 2.0,
+            light_speed: 0.0,
+            light_orbit_radius: LIGHT_ORBIT_RADIUS,
+            wobble_amplitude: 0.0,
+            wobble_frequency: 0.0,
+            mouse_x: f64::NAN,
+            mouse_y: f64::NAN,
+            cursor_mode: CursorMode::None,
+            cursor_strength: CURSOR_STRENGTH,
+            influence_radius: INFLUENCE_RADIUS,
+            max_displacement: MAX_DISPLACEMENT,
+            spring_stiffness: SPRING_STIFFNESS,
+            spring_damping: SPRING_DAMPING,
+            use_ortho: false,
+            center_x,
+            center_y,
+            paused: false,
+            hidden_paused: false,
+            blur_speed_factor: BLUR_SPEED_FACTOR,
+            pre_blur_speed: None,
+            blurred_speed: 0.0,
+            last_time: 0.0,
+            loop_generation: 0,
+            vertical_amplitude: 0.0,
+            vertical_frequency: 1.0,
+            depth_fade: true,
+            min_opacity: MIN_OPACITY,
+            opacity_curve: OpacityCurve::Linear,
+            hue_offset: 0.0,
+            hue_cycle_speed: 0.0,
+            depth_color_blend: 0.0,
+            colors_dirty: false,
+            color_scheme: ColorScheme::Rainbow,
+            font_family: FONT_FAMILY.to_string(),
+            font_weight: FONT_WEIGHT.to_string(),
+            skew_intensity: SKEW_INTENSITY,
+            background_color: String::new(),
+            highlight_color: HIGHLIGHT_COLOR.to_string(),
+            stroke_width: 0.0,
+            text_direction: TextDirection::Auto,
+            text_transform,
+            trail_length: 0,
+            trail_decay: 0.0,
+            stroke_color: "#000000".to_string(),
+            stroke_opacity: 1.0,
+            text_shadow: None,
+            shadow_perspective: false,
+            sync_url_hash: false,
+            text_feed: None,
+            text_feed_url: None,
+            text_feed_handlers: Vec::new(),
+            text_feed_retry_timer: None,
+            text_feed_retry_delay: FEED_RETRY_DELAY,
+            tab_sync: None,
+            text_loading: false,
+            text_fetch_generation: 0,
+            max_clipboard_chars: MAX_CLIPBOARD_CHARS,
+            clipboard_paste_enabled: false,
+            drop_target_active: false,
+            ticker_mode: false,
+            ticker_queue: VecDeque::new(),
+            ticker_hue: 0.0,
+            free_list: Vec::new(),
+            char_hover_callback: None,
+            click_callback: None,
+            click_through: false,
+            audio_context: None,
+            audio_enabled: true,
+            char_sound_map: Vec::new(),
+            mic_input: None,
+            frequency_bands: 0,
+            band_amplitude_factor: BAND_AMPLITUDE_FACTOR,
+            char_hover_listeners: Vec::new(),
+            letter_size: LETTER_SIZE,
+            letter_size_target: LETTER_SIZE,
+            letter_size_from: LETTER_SIZE,
+            letter_size_progress: 1.0,
+            letter_size_speed: LETTER_SIZE_SPEED,
+            glow_enabled: false,
+            max_glow: MAX_GLOW,
+            next_filter_id: 0,
+            sphere_radius: SPHERE_RADIUS,
+            sphere_radius_target: SPHERE_RADIUS,
+            sphere_radius_from: SPHERE_RADIUS,
+            sphere_radius_progress: 1.0,
+            sphere_radius_speed: RADIUS_SPEED,
+            sphere_shadow: false,
+            shadow_softness: SHADOW_SOFTNESS,
+            frame_times: [0.0; FPS_WINDOW],
+            frame_time_index: 0,
+            frame_time_count: 0,
+            show_fps: false,
+            adaptive_quality: false,
+            fps_low: QUALITY_LOW_FPS,
+            fps_high: QUALITY_HIGH_FPS,
+            quality_step: 1,
+            quality_letter_factor: 1.0,
+            quality_cooldown: 0.0,
+            drag_active: false,
+            recording: Vec::new(),
+            recording_remaining: 0,
+            playback_generation: 0,
+            resume_after_playback: false,
+            resize_debounce_ms: RESIZE_DEBOUNCE_MS,
+            respect_reduced_motion: true,
+            reduced_motion_speed: None,
+            drag_last_x: 0.0,
+            drag_sensitivity: DRAG_SENSITIVITY,
+            pinch_ids: None,
+            pinch_last_distance: 0.0,
+            gyro_enabled: false,
+            gyro_filtered: None,
+            spin: None,
+            spin_ease: EaseMode::EaseOut,
+            defer_updates: false,
+            deferred_updates: Vec::new(),
+            idle_flush_pending: false,
+        };
+
+        for config in [saved_config, hash_config].iter().flatten() {
+            text_sphere.apply_config(config);
+        }
+        text_sphere.update_accessible_text();
+        if media_query_matches(HIGH_CONTRAST_QUERY) {
+            text_sphere.set_high_contrast(true);
+        }
+        text_sphere.apply_reduced_motion(media_query_matches(REDUCED_MOTION_QUERY));
+        Some(text_sphere)
+    }
+
+    /// Replace the primary ring's text
+    fn set_text(&mut self, text: &str) {
+        self.finish_morph();
+        let shown = self.text_transform.apply(text);
+        self.rings[0].source_text = text.to_string();
+        if self.ticker_mode {
+            self.rings[0].text = shown.clone();
+            self.push_ticker_text(&shown);
+            self.update_accessible_text();
+            return;
+        }
+        self.rings[0].set_text(self.renderer.as_ref(), &shown, self.center_x, self.center_y);
+        self.fit_scramble();
+        self.update_accessible_text();
+        self.colors_dirty = true;
+        self.apply_text_style();
+        self.apply_text_direction();
+        self.register_char_hover();
+    }
+
+    /// Change the case transform and re-apply it to every ring's text
+    fn set_text_transform(&mut self, transform: TextTransform) {
+        self.text_transform = transform;
+        if self.ticker_mode {
+            // Characters already queued keep their case; new text picks it up
+            return;
+        }
+        self.finish_morph();
+        for ring in &mut self.rings {
+            let shown = transform.apply(&ring.source_text);
+            ring.set_text(self.renderer.as_ref(), &shown, self.center_x, self.center_y);
+        }
+        self.fit_scramble();
+        self.update_accessible_text();
+        self.colors_dirty = true;
+        self.apply_text_style();
+        self.apply_text_direction();
+        self.register_char_hover();
+    }
+
+    /// Resolve each ring's reading direction from `text_direction`
+    fn apply_text_direction(&mut self) {
+        for ring in &mut self.rings {
+            let rtl = match self.text_direction {
+                TextDirection::Auto => detect_rtl(&ring.text),
+                TextDirection::Ltr => false,
+                TextDirection::Rtl => true,
+            };
+            ring.set_rtl(rtl);
+        }
+    }
+
+    /// Apply the current font and outline settings to every character
+    fn apply_text_style(&mut self) {
+        let deferring = self.deferring();
+        let mut strokes = Vec::new();
+        let renderer = self.renderer.as_ref();
+        for character in self.rings.iter().flat_map(|ring| &ring.characters) {
+            renderer.set_character_font(&character.element, &self.font_family, &self.font_weight);
+            if deferring {
+                strokes.push(DeferredUpdate::UpdateStroke(
+                    character.element.clone(),
+                    self.stroke_width,
+                    self.stroke_color.clone(),
+                ));
+            } else {
+                renderer.set_character_stroke(
+                    &character.element,
+                    self.stroke_width,
+                    &self.stroke_color,
+                    self.stroke_opacity,
+                );
+            }
+            self.apply_text_shadow(&character.element, 1.0);
+            for ghost in &character.ghosts {
+                renderer.set_character_font(ghost, &self.font_family, &self.font_weight);
+            }
+        }
+        for update in strokes {
+            self.queue_deferred(update);
+        }
+    }
+
+    /// Give a character the text shadow, its offsets multiplied by `scale`
+    fn apply_text_shadow(&self, handle: &CharHandle, scale: f64) {
+        match &self.text_shadow {
+            Some(shadow) => self.renderer.set_character_shadow(
+                handle,
+                shadow.dx * scale,
+                shadow.dy * scale,
+                shadow.blur,
+                &shadow.color,
+            ),
+            None => self
+                .renderer
+                .set_character_shadow(handle, 0.0, 0.0, 0.0, ""),
+        }
+    }
+
+    /// Append a new ring and return its index
+    fn add_ring(&mut self, text: &str, radius: f64, speed: f64) -> usize {
+        let mut ring = OrbitRing::new(
+            self.renderer.as_ref(),
+            &self.text_transform.apply(text),
+            radius,
+            speed,
+            self.center_x,
+            self.center_y,
+        );
+        ring.source_text = text.to_string();
+        ring.id = self.next_ring_id;
+        self.next_ring_id += 1;
+        self.rings.push(ring);
+        self.apply_text_style();
+        self.apply_text_direction();
+        self.rings.len() - 1
+    }
+
+    /// Replace the text with `text` wrapped into rings of at most
+    /// `max_chars` characters: the first line on the primary ring, the rest
+    /// on new rings tilted alternately above and below it
+    fn wrap_and_display(&mut self, text: &str, max_chars: usize) {
+        let lines = wrap_text(text, max_chars);
+        let Some((first, rest)) = lines.split_first() else {
+            return;
+        };
+        while self.rings.len() > 1 {
+            self.remove_ring(self.rings.len() - 1);
+        }
+        self.set_text(first);
+        self.rings[0].tilt_target = 0.0;
+
+        let radius = self.rings[0].radius_target;
+        let speed = self.primary_speed_target();
+        for (i, line) in rest.iter().enumerate() {
+            // 30°, -30°, 60°, -60°, ...
+            let step = (i / 2 + 1) as f64 * WRAP_RING_TILT;
+            let degrees = if i % 2 == 0 { step } else { -step };
+            let index = self.add_ring(line, radius, speed);
+            self.rings[index].tilt_target = inclination_from_degrees(degrees);
+        }
+    }
+
+    /// Current index of the ring with the given id
+    fn ring_index(&self, id: u32) -> Option<usize> {
+        self.rings.iter().position(|ring| ring.id == id)
+    }
+
+    /// Remove a secondary ring and its characters.
+    /// Returns false if the index is out of range or refers to the primary ring.
+    fn remove_ring(&mut self, index: usize) -> bool {
+        if index == 0 || index >= self.rings.len() {
+            return false;
+        }
+        self.rings
+            .remove(index)
+            .remove_elements(self.renderer.as_ref());
+        true
+    }
+
+    /// Displacement caused by the cursor for a character at (x, y): inverse
+    /// square of the distance, easing smoothly to zero at the influence radius
+    fn cursor_offset(&self, x: f64, y: f64) -> (f64, f64) {
+        if self.cursor_mode == CursorMode::None {
+            return (0.0, 0.0);
+        }
+
+        let (dx, dy) = (x - self.mouse_x, y - self.mouse_y);
+        let distance = dx.hypot(dy);
+        // NaN means the cursor has not entered the SVG yet
+        if distance.is_nan() || distance >= self.influence_radius {
+            return (0.0, 0.0);
+        }
+
+        if distance == 0.0 {
+            // Cursor exactly on the character: attraction is already
+            // satisfied, repulsion pushes outward from the sphere's centre
+            if self.cursor_mode == CursorMode::Attract {
+                return (0.0, 0.0);
+            }
+            let (rx, ry) = (x - self.center_x, y - self.center_y);
+            let length = rx.hypot(ry);
+            let (ux, uy) = if length > 0.0 {
+                (rx / length, ry / length)
+            } else {
+                (1.0, 0.0)
+            };
+            return (ux * self.max_displacement, uy * self.max_displacement);
+        }
+
+        let falloff = (1.0 - distance / self.influence_radius).powi(2);
+        let force =
+            (self.cursor_strength / (distance * distance) * falloff).min(self.max_displacement);
+        let (ux, uy) = (dx / distance, dy / distance);
+        match self.cursor_mode {
+            // Never pull a character past the cursor
+            CursorMode::Attract => {
+                let pull = force.min(distance);
+                (-ux * pull, -uy * pull)
+            }
+            CursorMode::Repel => (ux * force, uy * force),
+            CursorMode::None => (0.0, 0.0),
+        }
+    }
+
+    /// Move each character's cursor offset: it follows the cursor while in
+    /// range and springs back to the orbit once released
+    fn update_cursor_springs(&mut self, delta: f64, char_data: &mut [CharRenderData]) {
+        let dt = delta.min(MAX_SPRING_STEP);
+
+        for data in char_data.iter_mut() {
+            let character = &self.rings[data.ring].characters[data.index];
+            let (old_x, old_y) = (character.offset_x, character.offset_y);
+            let (target_x, target_y) =
+                self.cursor_offset(data.screen_x - old_x, data.screen_y - old_y);
+
+            let (k, damping) = (self.spring_stiffness, self.spring_damping);
+            let character = &mut self.rings[data.ring].characters[data.index];
+            if target_x != 0.0 || target_y != 0.0 {
+                character.offset_x = target_x;
+                character.offset_y = target_y;
+                character.vel_x = 0.0;
+                character.vel_y = 0.0;
+            } else {
+                // F = -k * offset - damping * vel (semi-implicit Euler, unit mass)
+                character.vel_x += (-k * character.offset_x - damping * character.vel_x) * dt;
+                character.vel_y += (-k * character.offset_y - damping * character.vel_y) * dt;
+                character.offset_x += character.vel_x * dt;
+                character.offset_y += character.vel_y * dt;
+            }
+
+            data.screen_x += character.offset_x - old_x;
+            data.screen_y += character.offset_y - old_y;
+        }
+    }
+
+    /// Draw each character's trail ghosts from its recorded positions, then
+    /// record the current frame. Returns the visible ghosts as (z, handle)
+    /// sorted back to front.
+    fn update_trails(
+        &mut self,
+        char_data: &[CharRenderData],
+        recolor: bool,
+    ) -> Vec<(f64, CharHandle)> {
+        let mut visible = Vec::new();
+        if self.trail_length == 0 {
+            return visible;
+        }
+
+        for data in char_data {
+            let color = self.fill_for(data, 1.0);
+            let (length, decay) = (self.trail_length, self.trail_decay);
+            let renderer = self.renderer.as_ref();
+            let character = &mut self.rings[data.ring].characters[data.index];
+
+            // Grow the pool once per character rather than creating elements per frame
+            let created = character.ghosts.len() < length;
+            while character.ghosts.len() < length {
+                let placement = Placement {
+                    x: data.screen_x,
+                    y: data.screen_y,
+                    font_size: data.font_size,
+                    opacity: 1.0,
+                    scale_x: 1.0,
+                    skew: 0.0,
+                };
+                let ghost = renderer.create_character(&character.glyph, &color, &placement);
+                renderer.set_character_font(&ghost, &self.font_family, &self.font_weight);
+                character.ghosts.push(ghost);
+            }
+
+            for (age, ghost) in character.ghosts.iter().enumerate() {
+                let Some(point) = character.trail.get(age) else {
+                    renderer.update_character(ghost, &Placement::HIDDEN);
+                    continue;
+                };
+                let opacity = point.opacity * decay.powi(age as i32 + 1);
+                renderer.update_character(
+                    ghost,
+                    &Placement {
+                        x: point.screen_x,
+                        y: point.screen_y,
+                        font_size: point.font_size,
+                        opacity,
+                        scale_x: point.scale_x,
+                        skew: point.skew,
+                    },
+                );
+                if recolor || created {
+                    renderer.set_character_fill(ghost, &color);
+                }
+                if opacity > 0.0 {
+                    visible.push((point.z, ghost.clone()));
+                }
+            }
+
+            character.trail.push_front(TrailPoint {
+                screen_x: data.screen_x,
+                screen_y: data.screen_y,
+                font_size: data.font_size,
+                opacity: data.opacity,
+                z: data.z,
+                scale_x: data.scale_x,
+                skew: data.skew,
+            });
+            character.trail.truncate(length);
+        }
+
+        visible.sort_by(|a, b| a.0.total_cmp(&b.0));
+        visible
+    }
+
+    /// Set the trail length and decay, resizing or removing the ghost pools
+    fn set_trail(&mut self, length: usize, decay: f64) {
+        self.trail_length = length;
+        self.trail_decay = decay;
+        for character in self.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            for ghost in character.ghosts.drain(length.min(character.ghosts.len())..) {
+                self.renderer.remove(&ghost);
+            }
+            character.trail.truncate(length);
+        }
+    }
+
+    /// Spring each character's highlight scale towards its target size
+    fn update_highlights(&mut self, delta: f64) {
+        let dt = delta.min(MAX_SPRING_STEP);
+        let (k, damping) = (self.spring_stiffness, self.spring_damping);
+
+        for character in self.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            let target = if character.highlighted {
+                HIGHLIGHT_SCALE
+            } else {
+                1.0
+            };
+            let offset = character.highlight_scale - target;
+            if offset.abs() < 0.001 && character.highlight_vel.abs() < 0.001 {
+                // Settled: snap so unhighlighted characters drop back to exactly 1.0
+                character.highlight_scale = target;
+                character.highlight_vel = 0.0;
+                continue;
+            }
+            character.highlight_vel += (-k * offset - damping * character.highlight_vel) * dt;
+            character.highlight_scale += character.highlight_vel * dt;
+        }
+    }
+
+    /// Compute the projected screen state of one character
+    fn project_character(&self, ring_index: usize, index: usize) -> CharRenderData {
+        let ring = &self.rings[ring_index];
+        let fade = self.reveal_opacity(ring_index, index) * self.morph_opacity(ring_index);
+        self.project(
+            ring_index,
+            index,
+            &ring.characters[index],
+            ring.characters.len(),
+            fade,
+        )
+    }
+
+    /// Project `character`, the `index`th of `count` laid out on ring
+    /// `ring_index`, with its opacity scaled by `fade`
+    fn project(
+        &self,
+        ring_index: usize,
+        index: usize,
+        character: &Character,
+        count: usize,
+        fade: f64,
+    ) -> CharRenderData {
+        let ring = &self.rings[ring_index];
+        let radius_z = ring.orbit_radius_z;
+        // Frozen characters hold their pinned angle; everything below (depth,
+        // skew, occlusion, sort order) follows from it as usual
+        let angle = character
+            .frozen
+            .unwrap_or(character.base_angle + character.orbit_offset + ring.current_angle);
+
+        // 3D position in the XZ plane
+        let (x, orbit_z, dx, dz) = ring.orbit_point(&self.trig, angle);
+
+        // Outward normal, perpendicular to the direction of travel (dx, dz);
+        // for a circle this is (x, z) / R
+        let (normal_x, normal_z) = (dz, -dx);
+        let normal_len = normal_x.hypot(normal_z).max(f64::EPSILON);
+        let (normal_x, normal_z) = (normal_x / normal_len, normal_z / normal_len);
+
+        // Tilt the orbit plane around the X axis
+        // 0 = flat orbit, π/2 = vertical orbit (Ferris wheel)
+        // The wobble rocks every ring's plane about its own inclination
+        let inclination = ring.inclination
+            + self.wobble_amplitude * (TAU * self.wobble_frequency * self.elapsed).sin();
+        let y = -orbit_z * inclination.sin();
+        let z = orbit_z * inclination.cos();
+
+        // Perspective projection
+        // z > 0 = in front of center (closer to viewer), z < 0 = behind
+        // Orthographic mode keeps every character at full size; depth then
+        // only affects z-ordering, opacity and skew
+        let scale = if self.use_ortho {
+            1.0
+        } else {
+            // Keep the divisor positive if a character passes the camera
+            self.perspective_distance / (self.perspective_distance - z).max(1.0)
+        };
+
+        // Vertical wave, perspective-corrected so the back half bobs less
+        let wave_y = self.vertical_amplitude * (angle * self.vertical_frequency).sin() * scale;
+
+        // Helix: the string climbs from top to bottom along the orbit
+        let helix_y = (index as f64 / count as f64 - 0.5) * ring.helix_height * scale;
+
+        // Bounce: always upward (negative screen y), smaller towards the back
+        let bounce_y = if self.bounce_height > 0.0 {
+            -self.bounce_height * (angle * self.bounce_frequency).sin().abs() * scale
+        } else {
+            0.0
+        };
+
+        // Project position - centered at screen
+        let screen_x = self.center_x + x;
+        let screen_y = self.center_y + y + wave_y + helix_y + bounce_y;
+
+        // Cursor effects are a purely visual offset; the orbit is undisturbed
+        let screen_x = screen_x + character.offset_x;
+        let screen_y = screen_y + character.offset_y;
+        let mut font_size = self.letter_size * self.quality_letter_factor * scale;
+
+        // Frequency mode: each character follows the band nearest its place in the ring
+        if let Some(mic) = self.mic_input.as_ref().filter(|_| self.frequency_bands > 0) {
+            let bands = mic.band_levels.len();
+            let band = ((index as f64 + 0.5) * bands as f64 / count.max(1) as f64) as usize;
+            if let Some(level) = mic.band_levels.get(band.min(bands.saturating_sub(1))) {
+                font_size *= 1.0 + self.band_amplitude_factor * level;
+            }
+        }
+
+        // Size pulse, phase-shifted by orbit position so it ripples around the ring
+        // (beat pulses hit every character at once, on the beat)
+        if self.pulse_amplitude > 0.0 && self.beat_pulse {
+            let phase = ((self.elapsed - self.beat_origin) * self.pulse_frequency).rem_euclid(1.0);
+            font_size *= 1.0 + self.pulse_amplitude * (-BEAT_DECAY_RATE * phase).exp();
+        } else if self.pulse_amplitude > 0.0 {
+            let phase = TAU * self.pulse_frequency * self.elapsed + character.base_angle;
+            font_size = (font_size * (1.0 + self.pulse_amplitude * phase.sin())).max(1.0);
+        }
+
+        // Characters face outward along the orbit normal
+        // Width scale = cos(angle from front) = normal's z component (z / R for a circle)
+        // This naturally gives:
+        //   - Front (z = R): scale = 1.0 (full width, facing camera)
+        //   - Sides (z = 0): scale = 0.0 (edge-on)
+        //   - Back (z = -R): scale = -1.0 (full width, flipped/mirrored)
+        let scale_x = normal_z * inclination.cos();
+
+        // Calculate skew for "facing outward" effect
+        // Letters are painted on the sphere surface, facing radially outward
+        // The skew simulates viewing the letter's tangent plane at an angle
+        //
+        // For a letter at position (x, z):
+        // - At front (z = max, x = 0): faces camera directly → no skew
+        // - Moving right (z > 0, x > 0): left edge closer to camera → negative skew
+        // - Moving left (z > 0, x < 0): right edge closer to camera → positive skew
+        // - At back (z < 0): flipped, skew direction reverses
+        let skew = if self.skew_intensity > 0.0 && z.abs() > 1.0 {
+            // atan(nx/nz) gives the angle between the letter normal and camera direction
+            // Convert to degrees and scale by intensity
+            let skew_radians = (normal_x / scale_x).atan();
+            let skew_degrees = skew_radians * (180.0 / PI);
+            // Negative because SVG skewX shifts top-right for positive angles
+            // and we want the near edge (toward camera) to appear larger
+            -skew_degrees * self.skew_intensity
+        } else {
+            // Skew disabled, or near edge-on (z ≈ 0) where the letter is
+            // nearly invisible anyway
+            0.0
+        };
+
+        // Depth fade: front = fully opaque, back = min_opacity (or transparent)
+        // Characters skipped by adaptive quality are hidden rather than removed
+        let opacity = if !index.is_multiple_of(self.quality_step) {
+            0.0
+        } else if self.depth_fade {
+            self.opacity_curve.opacity(z / radius_z, self.min_opacity)
+        } else {
+            1.0
+        } * fade;
+        let opacity = if self.text_loading {
+            opacity.min(LOADING_OPACITY)
+        } else {
+            opacity
+        };
+        // High contrast: anything visible is fully opaque
+        let opacity = if self.high_contrast.is_some() && opacity > 0.0 {
+            1.0
+        } else {
+            opacity
+        };
+
+        CharRenderData {
+            ring: ring_index,
+            index,
+            screen_x,
+            screen_y,
+            font_size,
+            opacity,
+            z,
+            scale,
+            scale_x,
+            skew,
+            normal: (normal_x, normal_z * inclination.sin(), scale_x),
+        }
+    }
+
+    /// Effective fill of a character, including the sphere's shadow and
+    /// any lighting
+    fn color_for(&self, data: &CharRenderData) -> String {
+        let fill = self.fill_for(data, self.shadow_factor(data));
+        if self.lighting_active() && self.high_contrast.is_none() {
+            tint_color(&fill, self.light_tint(data))
+        } else {
+            fill
+        }
+    }
+
+    /// Fill of a character: its custom color if set, otherwise from the
+    /// color scheme (rainbow hues are scaled by `brightness`)
+    fn fill_for(&self, data: &CharRenderData, brightness: f64) -> String {
+        if self.high_contrast.is_some() {
+            return HIGH_CONTRAST_FOREGROUND.to_string();
+        }
+        let ring = &self.rings[data.ring];
+        if let Some(color) = &ring.characters[data.index].custom_color {
+            return color.clone();
+        }
+        match self.color_scheme.function() {
+            Some(scheme) => {
+                let z = (data.z / ring.orbit_radius_z).clamp(-1.0, 1.0);
+                scheme(data.index, ring.characters.len(), z, self.elapsed)
+            }
+            None => get_color_for_hue(self.hue_for(data), brightness),
+        }
+    }
+
+    /// Current hue of a character: its index hue shifted by the hue cycle,
+    /// blended towards a cold (back) to warm (front) hue by depth
+    fn hue_for(&self, data: &CharRenderData) -> f64 {
+        let ring = &self.rings[data.ring];
+        let base_hue = (ring.characters[data.index].base_hue + self.hue_offset).rem_euclid(360.0);
+
+        if self.depth_color_blend <= 0.0 {
+            return base_hue;
+        }
+
+        let depth = ((data.z / ring.orbit_radius_z) * 0.5 + 0.5).clamp(0.0, 1.0);
+        let depth_hue = DEPTH_COLD_HUE + (DEPTH_WARM_HUE - DEPTH_COLD_HUE) * depth;
+        base_hue * (1.0 - self.depth_color_blend) + depth_hue * self.depth_color_blend
+    }
+
+    /// Give every character its own glow filter (new characters from
+    /// `set_text`/`add_ring` are picked up on the next frame). Filters are
+    /// SVG-only, so canvas characters are drawn without glow.
+    fn ensure_glow_filters(&mut self) {
+        for ring in &mut self.rings {
+            for character in &mut ring.characters {
+                let Some(element) = character.element.element() else {
+                    continue;
+                };
+                if character.glow_filter.is_none() {
+                    let id = format!("{}-glow-{}", self.container.id(), self.next_filter_id);
+                    self.next_filter_id += 1;
+                    let filter = create_glow_filter(self.renderer.surface(), &id, 0.0);
+                    set_element_filter(element, &id);
+                    character.glow_filter = Some(filter);
+                }
+            }
+        }
+    }
+
+    fn set_glow_enabled(&mut self, enabled: bool) {
+        self.glow_enabled = enabled;
+        if enabled {
+            return;
+        }
+
+        for ring in &mut self.rings {
+            for character in &mut ring.characters {
+                if let Some(filter) = character.glow_filter.take() {
+                    if let Some(element) = character.element.element() {
+                        set_element_filter(element, "");
+                    }
+                    remove_element(&filter);
+                }
+            }
+        }
+    }
+
+    /// Brightness multiplier from the sphere's shadow: 1.0 when unshadowed,
+    /// falling to SHADOW_DARKNESS for characters directly behind the center.
+    /// Uses the projected 2D distance so the footprint is circular.
+    fn shadow_factor(&self, data: &CharRenderData) -> f64 {
+        if !self.sphere_shadow || data.z >= 0.0 {
+            return 1.0;
+        }
+
+        let shadow_radius = self.sphere_radius * data.scale;
+        let distance = (data.screen_x - self.center_x).hypot(data.screen_y - self.center_y);
+
+        // Fully shadowed inside the umbra, linear ramp across the penumbra
+        let umbra = (shadow_radius - self.shadow_softness).max(0.0);
+        let t = ((distance - umbra) / (shadow_radius - umbra).max(f64::EPSILON)).clamp(0.0, 1.0);
+        SHADOW_DARKNESS + (1.0 - SHADOW_DARKNESS) * t
+    }
+
+    /// Whether a character is nearer the viewer than the sphere's surface
+    /// along its line of sight (the midplane when outside the silhouette)
+    fn in_front_of_sphere(&self, data: &CharRenderData) -> bool {
+        let distance = (data.screen_x - self.center_x).hypot(data.screen_y - self.center_y);
+        let unprojected = distance / data.scale.max(f64::EPSILON);
+        let surface_z = (self.sphere_radius.powi(2) - unprojected.powi(2))
+            .max(0.0)
+            .sqrt();
+        data.z > surface_z
+    }
+
+    /// Start animating the sphere radius towards `radius`
+    fn set_sphere_radius_target(&mut self, radius: f64) {
+        self.sphere_radius_target = radius;
+        self.sphere_radius_from = self.sphere_radius;
+        self.sphere_radius_progress = 0.0;
+    }
+
+    /// Step the sphere radius towards its target
+    fn update_sphere_size(&mut self, delta: f64) {
+        if self.sphere_radius_progress < 1.0 {
+            let span = self.sphere_radius_target - self.sphere_radius_from;
+            self.sphere_radius_progress = if span.abs() > 0.01 {
+                (self.sphere_radius_progress + self.sphere_radius_speed * delta / span.abs())
+                    .min(1.0)
+            } else {
+                1.0
+            };
+            self.sphere_radius = self.sphere_radius_from
+                + span * easing::ease_in_out_cubic(self.sphere_radius_progress);
+            self.renderer.update_sphere(
+                &self.sphere,
+                self.center_x,
+                self.center_y,
+                self.sphere_radius,
+            );
+        }
+    }
+
+    /// Change the base letter size, clamped to 4–500 px. The rendered size
+    /// is this times the perspective scale, so it ranges from
+    /// `letter_size * D / (D + R)` at the back of the orbit to
+    /// `letter_size * D / (D - R)` at the front (D = perspective distance,
+    /// R = orbit depth radius). A running sphere animates to the new size;
+    /// a paused one is redrawn at it straight away.
+    fn set_letter_size(&mut self, size: f64) {
+        let size = size.clamp(MIN_LETTER_SIZE, MAX_LETTER_SIZE);
+        self.letter_size_target = size;
+        self.letter_size_from = self.letter_size;
+        self.letter_size_progress = 0.0;
+        if self.paused {
+            self.letter_size = size;
+            self.letter_size_progress = 1.0;
+            self.animate(0.0);
+        }
+    }
+
+    /// Step the letter size towards its target
+    fn update_letter_size(&mut self, delta: f64) {
+        if self.letter_size_progress < 1.0 {
+            let span = self.letter_size_target - self.letter_size_from;
+            self.letter_size_progress = if span.abs() > 0.01 {
+                (self.letter_size_progress + self.letter_size_speed * delta / span.abs()).min(1.0)
+            } else {
+                1.0
+            };
+            self.letter_size =
+                self.letter_size_from + span * easing::ease_in_out_cubic(self.letter_size_progress);
+        }
+    }
+
+    fn element(&self, data: &CharRenderData) -> &CharHandle {
+        &self.rings[data.ring].characters[data.index].element
+    }
+
+    fn record_frame_time(&mut self, delta: f64) {
+        self.frame_times[self.frame_time_index] = delta;
+        self.frame_time_index = (self.frame_time_index + 1) % FPS_WINDOW;
+        self.frame_time_count = (self.frame_time_count + 1).min(FPS_WINDOW);
+    }
+
+    /// Rolling average frame rate over the last FPS_WINDOW frames
+    fn fps(&self) -> f64 {
+        let total: f64 = self.frame_times[..self.frame_time_count].iter().sum();
+        if total > 0.0 {
+            self.frame_time_count as f64 / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Most recent frame delta in seconds
+    fn last_frame_time(&self) -> f64 {
+        self.frame_times[(self.frame_time_index + FPS_WINDOW - 1) % FPS_WINDOW]
+    }
+
+    /// Step quality down when FPS is low and back up once it recovers, at
+    /// most one step every QUALITY_STEP_INTERVAL seconds to avoid oscillation.
+    /// Steps: full → half-size letters → half-size letters, every other character.
+    fn update_adaptive_quality(&mut self, delta: f64) {
+        self.quality_cooldown -= delta;
+        if self.quality_cooldown > 0.0 || self.frame_time_count < FPS_WINDOW {
+            return;
+        }
+
+        let fps = self.fps();
+        let changed = if fps < self.fps_low {
+            if self.quality_letter_factor == 1.0 {
+                self.quality_letter_factor = 0.5;
+                true
+            } else if self.quality_step == 1 {
+                self.quality_step = 2;
+                true
+            } else {
+                false
+            }
+        } else if fps > self.fps_high {
+            if self.quality_step == 2 {
+                self.quality_step = 1;
+                true
+            } else if self.quality_letter_factor != 1.0 {
+                self.quality_letter_factor = 1.0;
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+
+        if changed {
+            log::info!(
+                "adaptive quality: {:.1} fps, letter factor {}, step {}",
+                fps,
+                self.quality_letter_factor,
+                self.quality_step
+            );
+            self.quality_cooldown = QUALITY_STEP_INTERVAL;
+        }
+    }
+
+    fn set_adaptive_quality(&mut self, enabled: bool) {
+        self.adaptive_quality = enabled;
+        if !enabled {
+            self.quality_step = 1;
+            self.quality_letter_factor = 1.0;
+        }
+    }
+
+    /// Front-most visible primary-ring character whose `font_size` square
+    /// (centered on its screen position) contains (x, y)
+    fn character_at(&self, x: f64, y: f64) -> Option<usize> {
+        (0..self.rings[0].characters.len())
+            .map(|i| self.project_character(0, i))
+            .filter(|data| {
+                let half = data.font_size / 2.0;
+                data.opacity > 0.0
+                    && (data.screen_x - x).abs() <= half
+                    && (data.screen_y - y).abs() <= half
+            })
+            .max_by(|a, b| a.z.total_cmp(&b.z))
+            .map(|data| data.index)
+    }
+
+    /// Start the warp burst: spin up, fly apart, then fade back in, each
+    /// phase taking a third of `duration`
+    fn start_warp(&mut self, duration: f64) {
+        // A warp restarted mid-flight keeps the speeds from before the first one
+        if matches!(self.warp_state, WarpState::Idle) {
+            self.warp_saved_speeds = self.rings.iter().map(|r| r.speed_target).collect();
+        }
+        self.warp_phase_duration = duration.max(0.0) / 3.0;
+        self.warp_state = WarpState::Accelerating(self.elapsed);
+    }
+
+    /// Progress (0 to 1) through the current warp phase
+    fn warp_progress(&self, start: f64) -> f64 {
+        if self.warp_phase_duration > 0.0 {
+            ((self.elapsed - start) / self.warp_phase_duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Advance the warp state machine and drive ring speeds while accelerating
+    fn update_warp(&mut self) {
+        let now = self.elapsed;
+        match self.warp_state {
+            WarpState::Idle => {}
+            WarpState::Accelerating(start) => {
+                let progress = self.warp_progress(start);
+                for (ring, &speed) in self.rings.iter_mut().zip(&self.warp_saved_speeds) {
+                    // Stationary rings spin up in the default direction
+                    let base = if speed == 0.0 { ROTATION_SPEED } else { speed };
+                    ring.rotation_speed = (base * WARP_ACCELERATION.powf(progress))
+                        .clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+                }
+                if progress >= 1.0 {
+                    self.warp_state = WarpState::Scattering(now);
+                }
+            }
+            WarpState::Scattering(start) => {
+                if self.warp_progress(start) >= 1.0 {
+                    for (ring, &speed) in self.rings.iter_mut().zip(&self.warp_saved_speeds) {
+                        ring.rotation_speed = speed;
+                    }
+                    self.warp_state = WarpState::Resetting(now);
+                }
+            }
+            WarpState::Resetting(start) => {
+                if self.warp_progress(start) >= 1.0 {
+                    self.warp_state = WarpState::Idle;
+                    self.queue_event("warp_complete", "{}".to_string());
+                }
+            }
+        }
+    }
+
+    /// Override projected positions while scattering, and fade characters
+    /// back in while resetting
+    fn apply_warp(&self, char_data: &mut [CharRenderData]) {
+        match self.warp_state {
+            WarpState::Scattering(start) => {
+                // Far enough to clear the screen from anywhere on it
+                let reach = self.center_x.hypot(self.center_y) * 2.0;
+                let push = self.warp_progress(start).powi(2) * reach;
+                for data in char_data.iter_mut() {
+                    let (dx, dy) = (data.screen_x - self.center_x, data.screen_y - self.center_y);
+                    let distance = dx.hypot(dy);
+                    if distance > 0.0 {
+                        data.screen_x += dx / distance * push;
+                        data.screen_y += dy / distance * push;
+                    }
+                }
+            }
+            WarpState::Resetting(start) => {
+                let progress = self.warp_progress(start);
+                for data in char_data.iter_mut() {
+                    data.opacity *= progress;
+                }
+            }
+            WarpState::Idle | WarpState::Accelerating(_) => {}
+        }
+    }
+
+    /// Queue a DOM event for after the current frame, if it is enabled
+    fn queue_event(&mut self, name: &str, detail_json: String) {
+        if self.enabled_events.contains(&name) {
+            self.pending_events.push((name.to_string(), detail_json));
+        }
+    }
+
+    /// Begin fading the primary ring in one character at a time
+    fn start_reveal(&mut self, char_delay: f64, fade_duration: f64) {
+        let count = self.rings[0].characters.len();
+        let sequence = self.reveal_order.sequence(count, &mut self.rng);
+
+        self.reveal_rank = vec![0; count];
+        for (rank, &index) in sequence.iter().enumerate() {
+            self.reveal_rank[index] = rank;
+        }
+
+        self.reveal_char_delay = char_delay.max(0.0);
+        self.reveal_fade_duration = fade_duration.max(0.0);
+        self.reveal_state = RevealState::Revealing {
+            next_char: 0,
+            elapsed: 0.0,
+        };
+    }
+
+    fn update_reveal(&mut self, delta: f64) {
+        if let RevealState::Revealing { next_char, elapsed } = &mut self.reveal_state {
+            *elapsed += delta;
+
+            // Count characters whose fade-in has started
+            while *next_char < self.reveal_rank.len()
+                && *elapsed >= *next_char as f64 * self.reveal_char_delay
+            {
+                *next_char += 1;
+            }
+
+            let last_start =
+                self.reveal_rank.len().saturating_sub(1) as f64 * self.reveal_char_delay;
+            if *next_char >= self.reveal_rank.len()
+                && *elapsed >= last_start + self.reveal_fade_duration
+            {
+                self.reveal_state = RevealState::Complete;
+                self.queue_event("reveal_complete", "{}".to_string());
+            }
+        }
+    }
+
+    /// Opacity multiplier from the reveal animation (1.0 once revealed)
+    fn reveal_opacity(&self, ring_index: usize, index: usize) -> f64 {
+        let RevealState::Revealing { elapsed, .. } = self.reveal_state else {
+            return 1.0;
+        };
+        if ring_index != 0 {
+            return 1.0;
+        }
+        // Characters added after the reveal started appear immediately
+        let Some(&rank) = self.reveal_rank.get(index) else {
+            return 1.0;
+        };
+
+        let since_start = elapsed - rank as f64 * self.reveal_char_delay;
+        if self.reveal_fade_duration > 0.0 {
+            easing::ease_in_out_quad((since_start / self.reveal_fade_duration).clamp(0.0, 1.0))
+        } else if since_start >= 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Cross-fade the primary ring to `text` over `duration` seconds: the
+    /// old characters fade out where they are while the new ones fade in.
+    /// Falls back to `set_text` in ticker mode, when paused or without a
+    /// duration.
+    fn morph_text(&mut self, text: &str, duration: f64) {
+        if self.ticker_mode || self.paused || duration <= 0.0 {
+            self.set_text(text);
+            return;
+        }
+        self.finish_morph();
+
+        let mut old_chars = std::mem::take(&mut self.rings[0].characters);
+        // Trails stop updating once a character leaves the ring
+        for character in &mut old_chars {
+            for ghost in character.ghosts.drain(..) {
+                self.renderer.remove(&ghost);
+            }
+        }
+        self.set_text(text);
+        self.morph = Some(MorphState {
+            old_chars,
+            progress: 0.0,
+            duration,
+        });
+    }
+
+    /// Opacity multiplier of ring `ring_index`'s characters during a morph
+    fn morph_opacity(&self, ring_index: usize) -> f64 {
+        match &self.morph {
+            Some(morph) if ring_index == 0 => easing::ease_in_out_cubic(morph.progress),
+            _ => 1.0,
+        }
+    }
+
+    /// Advance the morph, dropping the old characters once it completes
+    fn update_morph(&mut self, delta: f64) {
+        let Some(morph) = &mut self.morph else {
+            return;
+        };
+        morph.progress = (morph.progress + delta / morph.duration).min(1.0);
+        if morph.progress >= 1.0 {
+            self.finish_morph();
+        }
+    }
+
+    /// Position the fading-out characters of a morph, returning the visible
+    /// ones with their depth
+    fn draw_morph(&self) -> Vec<(f64, CharHandle)> {
+        let Some(morph) = &self.morph else {
+            return Vec::new();
+        };
+        let fade = 1.0 - easing::ease_in_out_cubic(morph.progress);
+        let count = morph.old_chars.len();
+        let mut visible = Vec::new();
+        for (i, character) in morph.old_chars.iter().enumerate() {
+            let data = self.project(0, i, character, count, fade);
+            self.renderer.update_character(
+                &character.element,
+                &Placement {
+                    x: data.screen_x,
+                    y: data.screen_y,
+                    font_size: data.font_size,
+                    opacity: data.opacity,
+                    scale_x: data.scale_x,
+                    skew: data.skew,
+                },
+            );
+            if data.opacity > 0.0 {
+                visible.push((data.z, character.element.clone()));
+            }
+        }
+        visible
+    }
+
+    /// End any morph immediately, removing the old characters
+    fn finish_morph(&mut self) {
+        if let Some(morph) = self.morph.take() {
+            for character in &morph.old_chars {
+                OrbitRing::remove_character(self.renderer.as_ref(), character);
+            }
+        }
+    }
+
+    /// Throw every character to a random point on its orbit, then spring
+    /// each back to its place over roughly `duration` seconds while the
+    /// rings keep turning. Uses the sphere's random generator, or a fresh
+    /// one seeded with `seed` for a repeatable shuffle.
+    fn randomize_positions(&mut self, duration: f64, seed: Option<u64>) {
+        if self.ticker_mode {
+            log::warn!("randomize_positions is not available in ticker mode");
+            return;
+        }
+        let mut seeded = seed.map(XorShift64::new);
+        let rng = seeded.as_mut().unwrap_or(&mut self.rng);
+        // The oscillation envelope decays by e^-6 over the duration
+        let omega = 12.0 / duration.max(0.01);
+        for character in self.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            let target_angle = character
+                .spring_to_base
+                .as_ref()
+                .map_or(character.base_angle, |spring| spring.target_angle);
+            character.base_angle = rng.next_f64() * TAU;
+            character.spring_to_base = Some(SpringToBase {
+                target_angle,
+                spring_k: omega * omega,
+                vel: 0.0,
+            });
+        }
+    }
+
+    /// Step randomized characters back towards their base angles, along
+    /// the shorter way round
+    fn update_springs_to_base(&mut self, delta: f64) {
+        let dt = delta.min(MAX_SPRING_STEP);
+        for character in self.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            let Some(spring) = &mut character.spring_to_base else {
+                continue;
+            };
+            let offset = (character.base_angle - spring.target_angle + PI).rem_euclid(TAU) - PI;
+            let offset = spring.step(offset, dt);
+            if offset.abs() < 1e-4 && spring.vel.abs() < 1e-3 {
+                character.base_angle = spring.target_angle;
+                character.spring_to_base = None;
+            } else {
+                character.base_angle = spring.target_angle + offset;
+            }
+        }
+    }
+
+    /// Cycle primary-ring characters through random glyphs for up to
+    /// `duration` seconds; each settles at its own random time
+    fn start_scramble(&mut self, duration: f64) {
+        let count = self.rings[0].characters.len();
+        let resolve_at = (0..count)
+            .map(|_| duration.max(0.0) * (0.5 + 0.5 * self.rng.next_f64()))
+            .collect();
+
+        self.scramble = Some(ScrambleState {
+            elapsed: 0.0,
+            resolve_at,
+            resolved: vec![false; count],
+        });
+    }
+
+    /// Forget scramble entries for characters the text no longer has, so a
+    /// scramble running when the text gets shorter still completes
+    fn fit_scramble(&mut self) {
+        let count = self.rings[0].characters.len();
+        if let Some(scramble) = &mut self.scramble {
+            scramble.resolve_at.truncate(count);
+            scramble.resolved.truncate(count);
+        }
+    }
+
+    fn update_scramble(&mut self, delta: f64) {
+        let Some(scramble) = &mut self.scramble else {
+            return;
+        };
+        scramble.elapsed += delta;
+
+        let renderer = self.renderer.as_ref();
+        let characters = &self.rings[0].characters;
+        for (i, character) in characters.iter().enumerate() {
+            // Characters added by set_text mid-scramble are left alone
+            let (Some(&resolve_at), Some(resolved)) =
+                (scramble.resolve_at.get(i), scramble.resolved.get_mut(i))
+            else {
+                continue;
+            };
+            if *resolved {
+                continue;
+            }
+
+            if scramble.elapsed >= resolve_at {
+                renderer.set_character_text(&character.element, &character.glyph);
+                *resolved = true;
+            } else {
+                let glyph = random_printable(&mut self.rng);
+                renderer.set_character_text(&character.element, &glyph.to_string());
+            }
+        }
+
+        if scramble.resolved.iter().all(|&r| r) {
+            self.scramble = None;
+            self.queue_event("scramble_complete", "{}".to_string());
+        }
+    }
+
+    fn animate(&mut self, delta: f64) {
+        self.elapsed += delta;
+
+        // Zero-length frames (e.g. forced redraws) don't count towards FPS
+        if delta > 0.0 {
+            self.record_frame_time(delta);
+        }
+        if self.show_fps {
+            self.renderer
+                .set_status_text(&format!("FPS: {:.1}", self.fps()));
+        }
+        if self.adaptive_quality {
+            self.update_adaptive_quality(delta);
+        }
+
+        self.update_warp();
+        if matches!(self.warp_state, WarpState::Idle) {
+            self.update_spin(delta);
+        }
+
+        // Update rotation angles (the user controls rotation while dragging)
+        let mic_speed_factor = self.update_mic_input();
+        let mut lapped = false;
+        for (r, ring) in self.rings.iter_mut().enumerate() {
+            if !self.drag_active {
+                lapped |= ring.advance(delta * mic_speed_factor) && r == 0;
+            }
+            // The warp drives ring speeds itself while it runs
+            if matches!(self.warp_state, WarpState::Idle) {
+                ring.update_speed(delta);
+            }
+            ring.update_tilt(delta);
+            ring.update_radius(delta);
+        }
+        self.update_ticker(delta);
+        self.update_morph(delta);
+        self.update_springs_to_base(delta);
+        if lapped {
+            self.lap_count += 1;
+            let detail = format!(
+                "{{\"lap_count\":{},\"elapsed_ms\":{}}}",
+                self.lap_count,
+                (self.elapsed * 1000.0).round()
+            );
+            self.queue_event("rotation_lap", detail);
+        }
+        self.update_sphere_size(delta);
+        self.update_letter_size(delta);
+        self.update_light(delta);
+
+        if self.glow_enabled {
+            self.ensure_glow_filters();
+        }
+
+        self.update_reveal(delta);
+        self.update_scramble(delta);
+
+        // Calculate positions for every character in every ring
+        let mut char_data: Vec<CharRenderData> = Vec::new();
+
+        for (r, ring) in self.rings.iter().enumerate() {
+            for i in 0..ring.characters.len() {
+                char_data.push(self.project_character(r, i));
+            }
+        }
+        self.update_cursor_springs(delta, &mut char_data);
+        self.update_highlights(delta);
+        self.apply_warp(&mut char_data);
+
+        // Sort by z (back to front - lowest z first, will be rendered first/behind)
+        // Characters within the same pixel of depth (e.g. stacked by the helix)
+        // are ordered by screen y so the lower one is drawn on top
+        char_data.sort_by(|a, b| {
+            a.z.round()
+                .total_cmp(&b.z.round())
+                .then(a.screen_y.total_cmp(&b.screen_y))
+        });
+
+        // Rotate the rainbow around the sphere
+        if self.hue_cycle_speed != 0.0 {
+            self.hue_offset = (self.hue_offset + self.hue_cycle_speed * delta).rem_euclid(360.0);
+        }
+
+        // Colors only need touching when they change over time or a color
+        // setting was just changed
+        let recolor = self.colors_dirty
+            || self.hue_cycle_speed != 0.0
+            || self.depth_color_blend > 0.0
+            || self.sphere_shadow
+            || self.lighting_active()
+            || self.color_scheme.is_animated();
+        self.colors_dirty = false;
+
+        // Update all character positions
+        for data in &char_data {
+            self.renderer.update_character(
+                self.element(data),
+                &Placement {
+                    x: data.screen_x,
+                    y: data.screen_y,
+                    font_size: data.font_size,
+                    opacity: data.opacity,
+                    scale_x: data.scale_x,
+                    skew: data.skew,
+                },
+            );
+
+            // Back-of-sphere characters cast a smaller shadow
+            if self.shadow_perspective && self.text_shadow.is_some() {
+                self.apply_text_shadow(self.element(data), data.scale);
+            }
+
+            let character = &self.rings[data.ring].characters[data.index];
+            // Queued once `character` is no longer borrowed
+            let mut deferred_color = None;
+            if recolor && !character.highlighted {
+                let color = self.color_for(data);
+                if self.deferring() {
+                    deferred_color = Some(DeferredUpdate::UpdateColor(
+                        character.element.clone(),
+                        color,
+                    ));
+                } else {
+                    self.renderer.set_character_fill(&character.element, &color);
+                }
+            }
+
+            // Highlighted characters, and ones still shrinking back afterwards
+            if character.highlighted || character.highlight_scale != 1.0 {
+                let fill = if character.highlighted {
+                    self.highlight_color.clone()
+                } else {
+                    self.color_for(data)
+                };
+                self.renderer.set_character_highlight(
+                    &character.element,
+                    character.highlight_scale,
+                    &fill,
+                );
+            }
+
+            // Glow intensifies towards the front of the orbit
+            if let (true, Some(filter)) = (self.glow_enabled, &character.glow_filter) {
+                let depth = (data.z / self.rings[data.ring].orbit_radius_z + 1.0) / 2.0;
+                update_glow_filter(filter, self.max_glow * depth.clamp(0.0, 1.0));
+            }
+
+            if let Some(update) = deferred_color {
+                self.queue_deferred(update);
+            }
+        }
+
+        // Fading-out morph characters are ordered by depth like trail ghosts
+        let mut ghosts = self.update_trails(&char_data, recolor);
+        ghosts.extend(self.draw_morph());
+        ghosts.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Reorder elements for proper z-ordering (back to front): DOM order
+        // for SVG, draw order for a canvas. char_data is sorted by z ascending
+        // (most negative/furthest first), merged across all rings; trail
+        // ghosts are interleaved by their z
+        let mut elements: Vec<&CharHandle> = Vec::with_capacity(char_data.len() + 1);
+        let mut ghosts = ghosts.iter().peekable();
+
+        let mut sphere_added = false;
+        for data in &char_data {
+            while let Some((_, ghost)) = ghosts.next_if(|(z, _)| *z <= data.z) {
+                elements.push(ghost);
+            }
+            // Add sphere when transitioning from behind to in-front
+            if !sphere_added && self.in_front_of_sphere(data) {
+                elements.push(&self.sphere);
+                sphere_added = true;
+            }
+            elements.push(self.element(data));
+        }
+        for (_, ghost) in ghosts {
+            elements.push(ghost);
+        }
+
+        // If all characters are behind sphere, add sphere last (on top)
+        if !sphere_added {
+            elements.push(&self.sphere);
+        }
+
+        if self.deferring() {
+            let elements = elements.into_iter().cloned().collect();
+            self.queue_deferred(DeferredUpdate::Reorder(elements));
+        } else {
+            self.renderer.reorder(&elements);
+        }
+        self.record_frame(delta);
+    }
+
+    /// Jump every ring to `angle` and redraw immediately. Non-finite
+    /// angles (including ones from tab sync) are ignored.
+    fn seek(&mut self, angle: f64) {
+        if !angle.is_finite() {
+            log::warn!("seek: ignoring non-finite angle {}", angle);
+            return;
+        }
+        let angle = angle.rem_euclid(2.0 * PI);
+        for ring in &mut self.rings {
+            ring.current_angle = angle;
+        }
+        self.animate(0.0);
+
+        // The next real frame must not see the time spent before the seek
+        self.last_time = 0.0;
+    }
+
+    /// Turn every ring by `delta_angle` and redraw immediately (works while paused)
+    fn nudge(&mut self, delta_angle: f64) {
+        for ring in &mut self.rings {
+            ring.current_angle = (ring.current_angle + delta_angle).rem_euclid(2.0 * PI);
+        }
+        self.animate(0.0);
+        self.last_time = 0.0;
+    }
+
+    /// Set the primary ring's speed at once, ignoring NaN and infinities
+    fn set_primary_speed(&mut self, speed: f64) {
+        if !speed.is_finite() {
+            log::warn!("ignoring non-finite rotation speed {}", speed);
+            return;
+        }
+        let speed = speed.clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+        self.spin = None;
+        self.rings[0].rotation_speed = speed;
+        self.rings[0].speed_target = speed;
+    }
+
+    /// Smoothly turn the primary ring clockwise (viewed from above) or
+    /// anticlockwise, keeping its speed
+    fn set_orbit_direction(&mut self, clockwise: bool) {
+        let ring = &mut self.rings[0];
+        let magnitude = ring.speed_target.abs();
+        ring.speed_target = if clockwise { magnitude } else { -magnitude };
+    }
+
+    fn change_primary_speed(&mut self, step: f64) {
+        self.set_primary_speed(self.rings[0].speed_target + step);
+    }
+
+    /// Speed the primary ring is heading for, including the end of a spin
+    fn primary_speed_target(&self) -> f64 {
+        self.spin
+            .as_ref()
+            .map_or(self.rings[0].speed_target, |spin| spin.to)
+    }
+
+    /// Ease the primary ring from its current speed to `target` over
+    /// `duration` seconds along `spin_ease`. A zero, negative or non-finite
+    /// duration sets the speed at once.
+    fn spin_up(&mut self, target: f64, duration: f64) {
+        let target = target.clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+        if !duration.is_finite() || duration <= 0.0 {
+            self.set_primary_speed(target);
+            return;
+        }
+        self.spin = Some(SpinTransition {
+            from: self.rings[0].rotation_speed,
+            to: target,
+            elapsed: 0.0,
+            duration,
+        });
+    }
+
+    /// Advance the spin transition by one frame. Both the speed and its
+    /// target follow the curve so the ring's own easing stays out of the way.
+    fn update_spin(&mut self, delta: f64) {
+        let Some(spin) = &mut self.spin else {
+            return;
+        };
+        spin.elapsed += delta;
+        let t = spin.elapsed / spin.duration;
+        let speed = spin.from + (spin.to - spin.from) * self.spin_ease.apply(t);
+        let ring = &mut self.rings[0];
+        if t >= 1.0 {
+            ring.rotation_speed = spin.to;
+            ring.speed_target = spin.to;
+            self.spin = None;
+        } else {
+            ring.rotation_speed = speed;
+            ring.speed_target = speed;
+        }
+    }
+
+    fn start_drag(&mut self, client_x: f64) {
+        self.drag_active = true;
+        self.drag_last_x = client_x;
+    }
+
+    /// Rotate all rings by the horizontal drag distance since the last event
+    fn drag_to(&mut self, client_x: f64) {
+        if !self.drag_active {
+            return;
+        }
+
+        // Dragging right moves the front of the orbit right (decreasing angle)
+        let rotation = -(client_x - self.drag_last_x) * self.drag_sensitivity;
+        self.drag_last_x = client_x;
+
+        for ring in &mut self.rings {
+            ring.current_angle = (ring.current_angle + rotation).rem_euclid(2.0 * PI);
+        }
+    }
+
+    fn end_drag(&mut self) {
+        self.drag_active = false;
+    }
+
+    fn start_pinch(&mut self, ids: (i32, i32), distance: f64) {
+        self.end_drag();
+        self.pinch_ids = Some(ids);
+        self.pinch_last_distance = distance;
+    }
+
+    /// Grow or shrink every ring by the change in finger distance
+    fn pinch_to(&mut self, distance: f64) {
+        let radius_delta = distance - self.pinch_last_distance;
+        self.pinch_last_distance = distance;
+
+        for ring in &mut self.rings {
+            ring.set_radii(
+                pinched_radius(ring.orbit_radius_x, radius_delta),
+                pinched_radius(ring.orbit_radius_z, radius_delta),
+            );
+        }
+    }
+
+    fn end_pinch(&mut self) {
+        self.pinch_ids = None;
+    }
+
+    /// Fit the drawing surface to a new container size, measuring the container when the
+    /// size isn't known (or is empty)
+    fn resize(&mut self, size: Option<(f64, f64)>) {
+        let size = size
+            .filter(|&(w, h)| w > 0.0 && h > 0.0)
+            .or_else(|| container_size(&self.container));
+        if let Some((w, h)) = size {
+            self.center_x = w / 2.0;
+            self.center_y = h / 2.0;
+
+            self.renderer.resize(w, h);
+            self.renderer.update_sphere(
+                &self.sphere,
+                self.center_x,
+                self.center_y,
+                self.sphere_radius,
+            );
+        }
+    }
+
+    /// Move the drawing surface and live region into `container` and fit
+    /// the scene to its size
+    fn move_to_container(&mut self, container: web_sys::Element) -> Result<(), JsValue> {
+        if let Some(surface) = self.renderer.surface().dyn_ref::<web_sys::Element>() {
+            container.append_child(surface)?;
+        }
+        container.append_child(&self.live_region)?;
+        if let Some((observer, _)) = &self.resize_observer {
+            observer.unobserve(&self.container);
+            observer.observe(&container);
+        }
+        self.container = container;
+
+        let rect = self.container.get_bounding_client_rect();
+        self.resize(Some((rect.width(), rect.height())));
+        self.animate(0.0);
+        Ok(())
+    }
+
+    /// Viewport rect `[x, y, width, height, top, left]` of the container,
+    /// or all zeros when it is not in the document
+    fn container_rect(&self) -> [f64; 6] {
+        if !self.container.is_connected() {
+            return [0.0; 6];
+        }
+        dom_rect_fields(&self.container.get_bounding_client_rect())
+    }
+
+    /// Viewport rect of the sphere circle. Canvas and WebGL draw it without
+    /// an element, so it is placed from the surface's rect.
+    fn sphere_screen_rect(&self) -> [f64; 6] {
+        let element = match self.sphere.element() {
+            Some(element) => element.dyn_ref::<web_sys::Element>(),
+            None => self.renderer.surface().dyn_ref::<web_sys::Element>(),
+        };
+        let Some(element) = element.filter(|element| element.is_connected()) else {
+            return [0.0; 6];
+        };
+        let rect = element.get_bounding_client_rect();
+        if self.sphere.element().is_some() {
+            return dom_rect_fields(&rect);
+        }
+        let size = 2.0 * self.sphere_radius;
+        let left = rect.left() + self.center_x - self.sphere_radius;
+        let top = rect.top() + self.center_y - self.sphere_radius;
+        [left, top, size, size, top, left]
+    }
+
+    /// Remove everything the sphere added to the page: its event listeners,
+    /// text feed, live region and SVG (or canvas). The animation loop stops
+    /// at its next frame.
+    fn destroy(&mut self) {
+        self.paused = true;
+        self.close_text_feed();
+        self.close_tab_sync();
+        self.close_mic_input();
+        if let Some(context) = self.audio_context.take() {
+            let _ = context.close();
+        }
+        if let Some((observer, _)) = self.resize_observer.take() {
+            observer.disconnect();
+        }
+        for listener in self.listeners.drain(..) {
+            for event in listener.events {
+                let _ = listener
+                    .target
+                    .remove_event_listener_with_callback(event, &listener.callback);
+            }
+        }
+        self.live_region.remove();
+        remove_element(self.renderer.surface());
+    }
+
+    /// Switch to drawing on a `<canvas>` in the container
+    fn use_canvas_backend(&mut self) -> Result<(), JsValue> {
+        if self.renderer.backend() == RenderBackend::Canvas {
+            return Ok(());
+        }
+        let (width, height) = container_size(&self.container)
+            .ok_or_else(|| JsValue::from_str("could not measure the container"))?;
+        let canvas = CanvasRenderer::new(&self.container, width, height)?;
+        self.replace_renderer(Box::new(canvas))
+    }
+
+    /// Draw with `renderer` from now on: every character and the sphere are
+    /// recreated in it, listeners move to its surface and the old surface is
+    /// removed. Glow filters and background gradients don't carry over.
+    fn replace_renderer(&mut self, renderer: Box<dyn Renderer>) -> Result<(), JsValue> {
+        self.finish_morph();
+        let old = std::mem::replace(&mut self.renderer, renderer);
+
+        let renderer = self.renderer.as_ref();
+        self.sphere = renderer.create_sphere(self.center_x, self.center_y, self.sphere_radius);
+        for character in self.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            // Filters and ghosts lived in the old surface; ghosts regrow on the next frame
+            character.glow_filter = None;
+            character.ghosts.clear();
+            character.element = renderer.create_character(&character.glyph, "", &Placement::HIDDEN);
+        }
+        self.free_list.clear();
+
+        let from = old.surface();
+        let to: web_sys::EventTarget = self.renderer.surface().clone().unchecked_into();
+        for listener in &mut self.listeners {
+            if AsRef::<JsValue>::as_ref(&listener.target) != from {
+                continue;
+            }
+            for event in &listener.events {
+                let _ = listener
+                    .target
+                    .remove_event_listener_with_callback(event, &listener.callback);
+                to.add_event_listener_with_callback(event, &listener.callback)?;
+            }
+            listener.target = to.clone();
+        }
+        // Re-initializing WebGL on the same canvas keeps it
+        if from != self.renderer.surface() {
+            remove_element(from);
+        }
+
+        self.resize(None);
+        self.update_accessible_text();
+        self.renderer.set_background(&self.background_color);
+        if self.high_contrast.is_some() {
+            let white = HIGH_CONTRAST_FOREGROUND;
+            self.renderer.set_sphere_gradient(white, white, white);
+            self.renderer
+                .set_sphere_outline(HIGH_CONTRAST_BACKGROUND, HIGH_CONTRAST_OUTLINE);
+        } else {
+            let [highlight, mid, shadow] = &self.sphere_gradient;
+            self.renderer.set_sphere_gradient(highlight, mid, shadow);
+        }
+        self.colors_dirty = true;
+        self.apply_text_style();
+        self.register_char_hover();
+        if self.click_through {
+            set_pointer_events(self.renderer.surface(), false);
+        }
+        self.animate(0.0);
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SVG Export
+// ============================================================================
+
+/// Attributes the animation loop and page handlers keep updating for the
+/// live page (pointer handling, the accessible label) that a saved frame
+/// doesn't need
+const EXPORT_STRIPPED_ATTRIBUTES: [&str; 3] = ["style", "role", "aria-label"];
+
+/// Remove `EXPORT_STRIPPED_ATTRIBUTES` from `svg` and all its descendants
+fn strip_runtime_attributes(svg: &web_sys::Element) {
+    let mut elements = vec![svg.clone()];
+    if let Ok(descendants) = svg.query_selector_all("*") {
+        elements.extend(
+            (0..descendants.length())
+                .filter_map(|i| descendants.get(i)?.dyn_into::<web_sys::Element>().ok()),
+        );
+    }
+    for element in elements {
+        for name in EXPORT_STRIPPED_ATTRIBUTES {
+            let _ = element.remove_attribute(name);
+        }
+    }
+}
+
+/// Serialize the current frame as standalone SVG markup: the element's
+/// outer HTML without runtime attributes, with `xmlns` and a `viewBox`
+/// injected into the root tag if absent
+fn export_svg_markup(live: &web_sys::Element) -> String {
+    // Strip a copy so the live SVG keeps its attributes
+    let svg: web_sys::Element = match live.clone_node_with_deep(true).map(JsCast::dyn_into) {
+        Ok(Ok(copy)) => copy,
+        _ => live.clone(),
+    };
+    strip_runtime_attributes(&svg);
+    let markup = svg.outer_html();
+    let Some(tag_end) = markup.find('>') else {
+        return markup;
+    };
+    let (open_tag, rest) = markup.split_at(tag_end);
+
+    let mut extra = String::new();
+    if !open_tag.contains("xmlns=") {
+        extra.push_str(" xmlns=\"http://www.w3.org/2000/svg\"");
+    }
+    if !open_tag.contains("viewBox=") {
+        let width = svg.get_attribute("width").unwrap_or_default();
+        let height = svg.get_attribute("height").unwrap_or_default();
+        extra.push_str(&format!(" viewBox=\"0 0 {} {}\"", width, height));
+    }
+
+    // Keep a self-closing "/>" intact
+    let insert_at = if open_tag.ends_with('/') {
+        open_tag.len() - 1
+    } else {
+        open_tag.len()
+    };
+    format!(
+        "{}{}{}{}",
+        &open_tag[..insert_at],
+        extra,
+        &open_tag[insert_at..],
+        rest
+    )
+}
+
+/// Trigger a browser download of `contents` using a Blob URL and a temporary anchor
+fn download_text(filename: &str, contents: &str, mime_type: &str) -> Result<(), JsValue> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let body = document
+        .body()
+        .ok_or_else(|| JsValue::from_str("no document body"))?;
+    let anchor: web_sys::HtmlAnchorElement = document.create_element("a")?.unchecked_into();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    // Firefox only follows anchors that are in the document
+    body.append_child(&anchor)?;
+    anchor.click();
+    anchor.remove();
+
+    // Revoking the URL before the download has started can cancel it
+    let revoke = Closure::once_into_js(move || {
+        let _ = web_sys::Url::revoke_object_url(&url);
+    });
+    web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no window"))?
+        .set_timeout_with_callback_and_timeout_and_arguments_0(revoke.unchecked_ref(), 0)?;
+    Ok(())
+}
+
+/// What `capture_frame` rasterizes: SVG markup of the given size, or a
+/// canvas that already holds the frame
+enum FrameSource {
+    Svg {
+        markup: String,
+        width: u32,
+        height: u32,
+    },
+    Canvas(web_sys::HtmlCanvasElement),
+}
+
+/// Encode a canvas's current contents as a PNG Blob
+async fn canvas_to_png(canvas: &web_sys::HtmlCanvasElement) -> Result<web_sys::Blob, JsValue> {
+    let mut result = Ok(());
+    let done = js_sys::Promise::new(&mut |resolve, reject| {
+        let callback = Closure::once_into_js(move |blob: JsValue| {
+            // toBlob passes null when the canvas can't be encoded
+            let _ = if blob.is_null() {
+                reject.call1(&JsValue::NULL, &JsValue::from_str("could not encode PNG"))
+            } else {
+                resolve.call1(&JsValue::NULL, &blob)
+            };
+        });
+        result = canvas.to_blob_with_type(callback.unchecked_ref(), "image/png");
+    });
+    result?;
+    Ok(JsFuture::from(done).await?.unchecked_into())
+}
+
+/// Rasterize SVG markup to a PNG Blob: load it into an `<img>` through a
+/// Blob URL, draw that onto an offscreen canvas of the given size and
+/// encode the canvas
+async fn svg_to_png(markup: &str, width: u32, height: u32) -> Result<web_sys::Blob, JsValue> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("image/svg+xml");
+    let parts = js_sys::Array::of1(&JsValue::from_str(markup));
+    let svg_blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&svg_blob)?;
+
+    let image = web_sys::HtmlImageElement::new()?;
+    let loaded = js_sys::Promise::new(&mut |resolve, reject| {
+        image.set_onload(Some(resolve.unchecked_ref()));
+        image.set_onerror(Some(reject.unchecked_ref()));
+    });
+    image.set_src(&url);
+    let load = JsFuture::from(loaded).await;
+    image.set_onload(None);
+    image.set_onerror(None);
+    web_sys::Url::revoke_object_url(&url)?;
+    load.map_err(|_| JsValue::from_str("could not load the SVG as an image"))?;
+
+    let canvas: web_sys::HtmlCanvasElement = document.create_element("canvas")?.unchecked_into();
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context: web_sys::CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("2d canvas context unavailable"))?
+        .dyn_into()?;
+    context.draw_image_with_html_image_element(&image, 0.0, 0.0)?;
+    canvas_to_png(&canvas).await
+}
+
+// ============================================================================
+// Snapshots
+// ============================================================================
+
+/// Animation state of the primary ring, for recording and replay
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    current_angle: f64,
+    elapsed_ms: f64,
+    char_positions: Vec<CharPosition>,
+    orbit_radius: f64,
+    rotation_speed: f64,
+    center_x: f64,
+    center_y: f64,
+}
+
+/// Rendered state of one character in a `Snapshot`
+#[derive(Serialize, Deserialize)]
+struct CharPosition {
+    #[serde(rename = "char")]
+    glyph: String,
+    screen_x: f64,
+    screen_y: f64,
+    z: f64,
+    opacity: f64,
+    scale_x: f64,
+    skew: f64,
+}
+
+impl TextSphere {
+    fn snapshot(&self) -> Snapshot {
+        let primary = &self.rings[0];
+        let char_positions = (0..primary.characters.len())
+            .map(|index| {
+                let data = self.project_character(0, index);
+                CharPosition {
+                    glyph: primary.characters[index].glyph.clone(),
+                    screen_x: data.screen_x,
+                    screen_y: data.screen_y,
+                    z: data.z,
+                    opacity: data.opacity,
+                    scale_x: data.scale_x,
+                    skew: data.skew,
+                }
+            })
+            .collect();
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            current_angle: primary.current_angle,
+            elapsed_ms: self.elapsed * 1000.0,
+            char_positions,
+            orbit_radius: primary.orbit_radius_x,
+            rotation_speed: primary.rotation_speed,
+            center_x: self.center_x,
+            center_y: self.center_y,
+        }
+    }
+
+    /// Return to the state of `snapshot` and redraw immediately. Character
+    /// positions are recomputed from the restored state; the snapshot's
+    /// characters only replace the text if they differ from it.
+    fn restore_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), String> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(format!(
+                "unsupported snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            ));
+        }
+        check_range(
+            "rotation_speed",
+            Some(snapshot.rotation_speed),
+            -MAX_ROTATION_SPEED..=MAX_ROTATION_SPEED,
+        )?;
+        check_range(
+            "orbit_radius",
+            Some(snapshot.orbit_radius),
+            MIN_ORBIT_RADIUS..=MAX_ORBIT_RADIUS,
+        )?;
+        if !(snapshot.current_angle.is_finite()
+            && snapshot.elapsed_ms.is_finite()
+            && snapshot.center_x.is_finite()
+            && snapshot.center_y.is_finite())
+        {
+            return Err("snapshot contains a non-finite value".to_string());
+        }
+
+        let glyphs: Vec<&str> = snapshot
+            .char_positions
+            .iter()
+            .map(|position| position.glyph.as_str())
+            .collect();
+        let unchanged = self.rings[0]
+            .characters
+            .iter()
+            .map(|character| character.glyph.as_str())
+            .eq(glyphs.iter().copied());
+        if !unchanged {
+            self.set_text(&glyphs.concat());
+        }
+
+        let primary = &mut self.rings[0];
+        let aspect = primary.orbit_radius_z / primary.orbit_radius_x;
+        primary.set_radii(snapshot.orbit_radius, snapshot.orbit_radius * aspect);
+        primary.current_angle = snapshot.current_angle.rem_euclid(2.0 * PI);
+        self.set_primary_speed(snapshot.rotation_speed);
+        self.elapsed = snapshot.elapsed_ms / 1000.0;
+        self.center_x = snapshot.center_x;
+        self.center_y = snapshot.center_y;
+        self.renderer.update_sphere(
+            &self.sphere,
+            self.center_x,
+            self.center_y,
+            self.sphere_radius,
+        );
+
+        self.animate(0.0);
+        // The next real frame must not see the time spent before the restore
+        self.last_time = 0.0;
+        Ok(())
+    }
+}
+
+/// A recorded frame: its snapshot and the time since the previous frame
+#[derive(Serialize, Deserialize)]
+struct FrameSnapshot {
+    delta_ms: f64,
+    #[serde(flatten)]
+    snapshot: Snapshot,
+}
+
+impl TextSphere {
+    /// Capture the next `frames` animation frames, discarding any earlier recording
+    fn start_recording(&mut self, frames: usize) {
+        self.recording = Vec::with_capacity(frames);
+        self.recording_remaining = frames;
+    }
+
+    /// Append the frame just drawn to the recording, firing
+    /// `recording_complete` with every frame once the last is captured
+    fn record_frame(&mut self, delta: f64) {
+        // Forced redraws (seek, restore) aren't frames
+        if self.recording_remaining == 0 || delta <= 0.0 {
+            return;
+        }
+        self.recording.push(FrameSnapshot {
+            delta_ms: delta * 1000.0,
+            snapshot: self.snapshot(),
+        });
+        self.recording_remaining -= 1;
+        if self.recording_remaining == 0 {
+            match serde_json::to_string(&self.recording) {
+                // Requested explicitly, so not subject to set_event_enabled
+                Ok(json) => self
+                    .pending_events
+                    .push(("recording_complete".to_string(), json)),
+                Err(e) => log::warn!("could not serialize recording: {}", e),
+            }
+        }
+    }
+}
+
+/// Recorded frames being replayed by `play_recording`
+struct Playback {
+    frames: Vec<FrameSnapshot>,
+    repeat: bool,
+    generation: u32, // Matches TextSphere::playback_generation while current
+}
+
+/// Show frame `index` of a playback, then schedule the next one after its
+/// recorded delta
+fn play_frame(text_sphere: Rc<RefCell<TextSphere>>, playback: Rc<Playback>, index: usize) {
+    let index = match index {
+        i if i < playback.frames.len() => i,
+        _ if playback.repeat && !playback.frames.is_empty() => 0,
+        _ => {
+            let resume = {
+                let mut ts = text_sphere.borrow_mut();
+                let current = ts.playback_generation == playback.generation;
+                let resume = current && std::mem::take(&mut ts.resume_after_playback);
+                if resume {
+                    ts.paused = false;
+                }
+                resume
+            };
+            if resume {
+                start_animation_loop(text_sphere);
+            }
+            return;
+        }
+    };
+
+    {
+        let mut ts = text_sphere.borrow_mut();
+        if ts.playback_generation != playback.generation {
+            return;
+        }
+        if let Err(e) = ts.restore_snapshot(&playback.frames[index].snapshot) {
+            log::warn!("play_recording: frame {}: {}", index, e);
+            return;
+        }
+    }
+
+    let next = playback
+        .frames
+        .get(index + 1)
+        .or(playback.frames.first().filter(|_| playback.repeat));
+    let delay = next.map_or(0.0, |frame| frame.delta_ms);
+    let callback = Closure::once_into_js(move || play_frame(text_sphere, playback, index + 1));
+    if let Some(window) = web_sys::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.unchecked_ref(),
+            delay.round() as i32,
+        );
+    }
+}
+
+// ============================================================================
+// JSON Configuration
+// ============================================================================
+
+/// Bulk configuration exchanged as JSON. Absent fields are left unchanged
+/// when applied; `inclination` is in degrees.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SphereConfig {
+    text: Option<String>,
+    rotation_speed: Option<f64>,
+    orbit_radius: Option<f64>,
+    letter_size: Option<f64>,
+    letter_spacing: Option<f64>,
+    start_angle: Option<f64>,
+    sphere_radius: Option<f64>,
+    perspective_distance: Option<f64>,
+    orthographic: Option<bool>,
+    skew_intensity: Option<f64>,
+    font_family: Option<String>,
+    font_weight: Option<String>,
+    vertical_amplitude: Option<f64>,
+    vertical_frequency: Option<f64>,
+    inclination: Option<f64>,
+    depth_fade: Option<bool>,
+    min_opacity: Option<f64>,
+    hue_cycle_speed: Option<f64>,
+    depth_color_blend: Option<f64>,
+    background_color: Option<String>, // `#rrggbb`, or "" / "transparent" for none
+    color_scheme: Option<String>,
+}
+
+/// Error unless `value` is absent or within `range`
+fn check_range(
+    name: &str,
+    value: Option<f64>,
+    range: std::ops::RangeInclusive<f64>,
+) -> Result<(), String> {
+    match value {
+        Some(v) if !range.contains(&v) => Err(format!(
+            "{} must be between {} and {}, got {}",
+            name,
+            range.start(),
+            range.end(),
+            v
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Decode `%XX` escapes (and `+` as a space) in a URL component.
+/// Malformed escapes are kept literally; invalid UTF-8 is replaced.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let escaped = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            if let Some(byte) = escaped {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(if bytes[i] == b'+' { b' ' } else { bytes[i] });
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Encode everything except unreserved characters as `%XX`
+fn percent_encode(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-_.~".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Read settings from a URL hash such as `#text=Hello&speed=0.5`.
+/// Unknown keys and unparseable numbers are skipped with a warning.
+fn parse_hash_config(hash: &str) -> SphereConfig {
+    let mut config = SphereConfig::default();
+    let hash = hash.strip_prefix('#').unwrap_or(hash);
+
+    for pair in hash.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+        let number = || match value.parse::<f64>() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                log::warn!("URL hash: {} is not a number: {}", key, value);
+                None
+            }
+        };
+        match percent_decode(key).as_str() {
+            "text" => config.text = Some(value.clone()),
+            "speed" => config.rotation_speed = number(),
+            "orbit_radius" => config.orbit_radius = number(),
+            "inclination" => config.inclination = number(),
+            "perspective" => config.perspective_distance = number(),
+            "font" => config.font_family = Some(value.clone()),
+            "bg_color" => config.background_color = Some(value.clone()),
+            other => log::warn!("URL hash: unknown key {}", other),
+        }
+    }
+    config
+}
+
+/// Inverse of `parse_hash_config` for the keys it understands
+fn build_hash_config(config: &SphereConfig) -> String {
+    let numbers = [
+        ("speed", config.rotation_speed),
+        ("orbit_radius", config.orbit_radius),
+        ("inclination", config.inclination),
+        ("perspective", config.perspective_distance),
+    ];
+    let strings = [
+        ("text", &config.text),
+        ("font", &config.font_family),
+        ("bg_color", &config.background_color),
+    ];
+
+    let mut pairs: Vec<String> = strings
+        .iter()
+        .filter_map(|(key, value)| {
+            value
+                .as_ref()
+                .map(|v| format!("{}={}", key, percent_encode(v)))
+        })
+        .collect();
+    pairs.extend(
+        numbers
+            .iter()
+            .filter_map(|(key, value)| value.map(|v| format!("{}={}", key, v))),
+    );
+    pairs.join("&")
+}
+
+/// Parse and validate a JSON configuration
+fn parse_config(json: &str) -> Result<SphereConfig, String> {
+    let config: SphereConfig =
+        serde_json::from_str(json).map_err(|e| format!("invalid config JSON: {}", e))?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Parse a configuration saved by `save_config`, clamping rather than
+/// rejecting out-of-range values so that one bad value (e.g. saved by an
+/// older version) doesn't discard every other setting
+fn parse_saved_config(json: &str) -> Result<SphereConfig, String> {
+    let config: SphereConfig =
+        serde_json::from_str(json).map_err(|e| format!("invalid config JSON: {}", e))?;
+    Ok(config.clamped())
+}
+
+impl SphereConfig {
+    /// Check every present value before any of them are applied
+    fn validate(&self) -> Result<(), String> {
+        check_range(
+            "rotation_speed",
+            self.rotation_speed,
+            -MAX_ROTATION_SPEED..=MAX_ROTATION_SPEED,
+        )?;
+        check_range(
+            "orbit_radius",
+            self.orbit_radius,
+            MIN_ORBIT_RADIUS..=MAX_ORBIT_RADIUS,
+        )?;
+        // Wider than MIN/MAX_LETTER_SIZE so configs saved before those
+        // limits still load; apply_config clamps them
+        check_range("letter_size", self.letter_size, 1.0..=1000.0)?;
+        check_range(
+            "letter_spacing",
+            self.letter_spacing,
+            0.0..=MAX_LETTER_SPACING,
+        )?;
+        check_range("start_angle", self.start_angle, -360.0..=360.0)?;
+        check_range("sphere_radius", self.sphere_radius, 0.0..=MAX_ORBIT_RADIUS)?;
+        if let Some(d) = self.perspective_distance.filter(|d| d.is_nan() || *d < 1.0) {
+            return Err(format!(
+                "perspective_distance must be at least 1, got {}",
+                d
+            ));
+        }
+        check_range("skew_intensity", self.skew_intensity, 0.0..=1.0)?;
+        check_range("inclination", self.inclination, -90.0..=90.0)?;
+        check_range("min_opacity", self.min_opacity, 0.0..=1.0)?;
+        check_range("depth_color_blend", self.depth_color_blend, 0.0..=1.0)?;
+        if let Some(weight) = &self.font_weight {
+            if !is_valid_font_weight(weight) {
+                return Err(format!("invalid font weight: {}", weight));
+            }
+        }
+        if let Some(color) = &self.background_color {
+            background_fill(color)?;
+        }
+        if let Some(name) = &self.color_scheme {
+            if ColorScheme::from_name(name).is_none() {
+                return Err(format!("unknown color scheme: {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pull every value into the range `validate` accepts, dropping those
+    /// that can't be (non-finite numbers, unknown names), so the result
+    /// always passes `validate`
+    fn clamped(mut self) -> Self {
+        fn clamp(value: Option<f64>, min: f64, max: f64) -> Option<f64> {
+            value.filter(|v| v.is_finite()).map(|v| v.clamp(min, max))
+        }
+        self.rotation_speed = clamp(self.rotation_speed, -MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+        self.orbit_radius = clamp(self.orbit_radius, MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS);
+        self.letter_size = clamp(self.letter_size, MIN_LETTER_SIZE, MAX_LETTER_SIZE);
+        self.letter_spacing = clamp(self.letter_spacing, 0.0, MAX_LETTER_SPACING);
+        self.start_angle = clamp(self.start_angle, -360.0, 360.0);
+        self.sphere_radius = clamp(self.sphere_radius, 0.0, MAX_ORBIT_RADIUS);
+        self.perspective_distance = clamp(self.perspective_distance, 1.0, f64::MAX);
+        self.skew_intensity = clamp(self.skew_intensity, 0.0, 1.0);
+        self.inclination = clamp(self.inclination, -90.0, 90.0);
+        self.min_opacity = clamp(self.min_opacity, 0.0, 1.0);
+        self.depth_color_blend = clamp(self.depth_color_blend, 0.0, 1.0);
+        self.font_weight = self.font_weight.filter(|w| is_valid_font_weight(w));
+        self.background_color = self
+            .background_color
+            .filter(|color| background_fill(color).is_ok());
+        self.color_scheme = self
+            .color_scheme
+            .filter(|name| ColorScheme::from_name(name).is_some());
+        self
+    }
+}
+
+impl TextSphere {
+    /// Snapshot of the current configuration with every field present,
+    /// clamped so that it always loads back
+    fn config(&self) -> SphereConfig {
+        let primary = &self.rings[0];
+        let mut config = SphereConfig {
+            text: Some(primary.source_text.clone()),
+            rotation_speed: Some(self.primary_speed_target()),
+            orbit_radius: Some(primary.radius_target),
+            letter_size: Some(self.letter_size_target),
+            letter_spacing: Some(primary.letter_spacing),
+            start_angle: Some(primary.start_angle.to_degrees()),
+            sphere_radius: Some(self.sphere_radius_target),
+            perspective_distance: Some(self.perspective_distance),
+            orthographic: Some(self.use_ortho),
+            skew_intensity: Some(self.skew_intensity),
+            font_family: Some(self.font_family.clone()),
+            font_weight: Some(self.font_weight.clone()),
+            vertical_amplitude: Some(self.vertical_amplitude),
+            vertical_frequency: Some(self.vertical_frequency),
+            inclination: Some(primary.tilt_target.to_degrees()),
+            depth_fade: Some(self.depth_fade),
+            min_opacity: Some(self.min_opacity),
+            hue_cycle_speed: Some(self.hue_cycle_speed),
+            depth_color_blend: Some(self.depth_color_blend),
+            background_color: (!self.background_color.is_empty())
+                .then(|| self.background_color.clone()),
+            color_scheme: Some(self.color_scheme.name().to_string()),
+        };
+        // Save the user's settings, not the high-contrast overrides
+        if let Some(saved) = &self.high_contrast {
+            config.depth_fade = Some(saved.depth_fade);
+            config.depth_color_blend = Some(saved.depth_color_blend);
+            config.background_color =
+                (!saved.background_color.is_empty()).then(|| saved.background_color.clone());
+        }
+        // ...nor the zero speed reduced motion holds the sphere at, or the
+        // slowdown while the window is blurred
+        if let Some(speed) = self.reduced_motion_speed.or(self.speed_before_blur()) {
+            config.rotation_speed = Some(speed);
+        }
+        config.clamped()
+    }
+
+    /// Persist the current configuration so it survives a page reload
+    /// Also mirrored into the URL hash when `sync_url_hash` is on
+    fn save_config(&self) {
+        let config = self.config();
+        match serde_json::to_string(&config) {
+            Ok(json) => save_to_local_storage(&self.storage_key, &json),
+            Err(e) => log::warn!("could not serialize settings: {}", e),
+        }
+        // replaceState rather than location.hash, which would add a history
+        // entry per change and fire hashchange
+        if self.sync_url_hash {
+            if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+                let url = format!("#{}", build_hash_config(&config));
+                let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&url));
+            }
+        }
+    }
+
+    /// Fill the background with a CSS color, or remove it (None)
+    fn set_background(&mut self, fill: Option<String>) {
+        self.background_color = fill.unwrap_or_default();
+        self.renderer.set_background(&self.background_color);
+    }
+
+    /// Apply every present field of an already validated config
+    fn apply_config(&mut self, config: &SphereConfig) {
+        if let Some(text) = &config.text {
+            self.set_text(text);
+        }
+        if let Some(speed) = config.rotation_speed {
+            self.set_primary_speed(speed);
+        }
+        if let Some(radius) = config.orbit_radius {
+            self.rings[0].set_radii(radius, radius);
+        }
+        if let Some(size) = config.letter_size {
+            let size = size.clamp(MIN_LETTER_SIZE, MAX_LETTER_SIZE);
+            self.letter_size = size;
+            self.letter_size_target = size;
+            self.letter_size_progress = 1.0;
+        }
+        if let Some(spacing) = config.letter_spacing {
+            self.rings[0].set_letter_spacing(spacing);
+        }
+        if let Some(degrees) = config.start_angle {
+            for ring in &mut self.rings {
+                ring.set_start_angle(degrees.to_radians());
+            }
+        }
+        if let Some(radius) = config.sphere_radius {
+            self.sphere_radius = radius;
+            self.sphere_radius_target = radius;
+            self.sphere_radius_progress = 1.0;
+            self.renderer
+                .update_sphere(&self.sphere, self.center_x, self.center_y, radius);
+        }
+        if let Some(d) = config.perspective_distance {
+            self.perspective_distance = d;
+        }
+        if let Some(ortho) = config.orthographic {
+            self.use_ortho = ortho;
+        }
+        if let Some(intensity) = config.skew_intensity {
+            self.skew_intensity = intensity;
+        }
+        if let Some(family) = &config.font_family {
+            self.font_family = css_font_family(family);
+        }
+        if let Some(weight) = &config.font_weight {
+            self.font_weight = weight.clone();
+        }
+        if let Some(amplitude) = config.vertical_amplitude {
+            self.vertical_amplitude = amplitude;
+        }
+        if let Some(frequency) = config.vertical_frequency {
+            self.vertical_frequency = frequency;
+        }
+        if let Some(degrees) = config.inclination {
+            let inclination = inclination_from_degrees(degrees);
+            for ring in &mut self.rings {
+                ring.inclination = inclination;
+                ring.tilt_target = inclination;
+            }
+        }
+        if let Some(enabled) = config.depth_fade {
+            self.depth_fade = enabled;
+        }
+        if let Some(opacity) = config.min_opacity {
+            self.min_opacity = opacity;
+        }
+        if let Some(speed) = config.hue_cycle_speed {
+            self.hue_cycle_speed = speed;
+        }
+        if let Some(blend) = config.depth_color_blend {
+            self.depth_color_blend = blend;
+            self.colors_dirty = true;
+        }
+        if let Some(color) = &config.background_color {
+            // Already validated
+            self.set_background(background_fill(color).unwrap_or_default());
+        }
+        if let Some(scheme) = config
+            .color_scheme
+            .as_deref()
+            .and_then(ColorScheme::from_name)
+        {
+            self.color_scheme = scheme;
+            self.colors_dirty = true;
+        }
+        self.apply_text_style();
+    }
+}
+
+// ============================================================================
+// Global State
+// ============================================================================
+
+thread_local! {
+    /// The running spheres by instance id, shared with the exported WASM API
+    static TEXT_SPHERES: RefCell<HashMap<u32, Rc<RefCell<TextSphere>>>> = RefCell::new(HashMap::new());
+    static NEXT_INSTANCE_ID: Cell<u32> = const { Cell::new(0) };
+}
+
+fn text_sphere_handle(instance_id: u32) -> Option<Rc<RefCell<TextSphere>>> {
+    let handle = TEXT_SPHERES.with(|spheres| spheres.borrow().get(&instance_id).cloned());
+    if handle.is_none() {
+        log::warn!("no sphere instance {}", instance_id);
+    }
+    handle
+}
+
+/// Run `f` against the sphere `instance_id`, if it exists
+fn with_text_sphere<R>(instance_id: u32, f: impl FnOnce(&mut TextSphere) -> R) -> Option<R> {
+    text_sphere_handle(instance_id).map(|text_sphere| f(&mut text_sphere.borrow_mut()))
+}
+
+/// Like `with_text_sphere`, then save the settings to localStorage
+fn with_text_sphere_saved<R>(instance_id: u32, f: impl FnOnce(&mut TextSphere) -> R) -> Option<R> {
+    with_text_sphere(instance_id, |ts| {
+        let result = f(ts);
+        ts.save_config();
+        result
+    })
+}
+
+/// localStorage key for a sphere's settings; the default container keeps
+/// the original key so settings saved before multiple instances still load
+fn config_storage_key(container_id: &str) -> String {
+    if container_id == DEFAULT_CONTAINER_ID {
+        CONFIG_STORAGE_KEY.to_string()
+    } else {
+        format!("{}-{}", CONFIG_STORAGE_KEY, container_id)
+    }
+}
+
+/// Layout size of a sphere's container, or the window's when the
+/// container has none yet
+fn container_size(container: &web_sys::Element) -> Option<(f64, f64)> {
+    let (width, height) = (container.client_width(), container.client_height());
+    if width > 0 && height > 0 {
+        return Some((width as f64, height as f64));
+    }
+    let window = web_sys::window()?;
+    Some((
+        window.inner_width().ok()?.as_f64()?,
+        window.inner_height().ok()?.as_f64()?,
+    ))
+}
+
+/// `[x, y, width, height, top, left]` of a DOMRect
+fn dom_rect_fields(rect: &web_sys::DomRect) -> [f64; 6] {
+    [
+        rect.x(),
+        rect.y(),
+        rect.width(),
+        rect.height(),
+        rect.top(),
+        rect.left(),
+    ]
+}
+
+/// An event listener owned by a sphere
+struct Listener {
+    target: web_sys::EventTarget,
+    events: Vec<&'static str>,
+    callback: js_sys::Function,
+    _closure: Box<dyn std::any::Any>, // Keeps `callback` alive until the listener is removed
+}
+
+/// Listen for `events` on `target` until the sphere is destroyed
+fn add_listener<T: ?Sized + 'static>(
+    text_sphere: &Rc<RefCell<TextSphere>>,
+    target: &web_sys::EventTarget,
+    events: &[&'static str],
+    closure: Closure<T>,
+) {
+    let callback: js_sys::Function = closure.as_ref().clone().unchecked_into();
+    for event in events {
+        target
+            .add_event_listener_with_callback(event, &callback)
+            .unwrap_or_else(|_| panic!("should add {} listener", event));
+    }
+    text_sphere.borrow_mut().listeners.push(Listener {
+        target: target.clone(),
+        events: events.to_vec(),
+        callback,
+        _closure: Box::new(closure),
+    });
+}
+
+// ============================================================================
+// Animation Loop
+// ============================================================================
+
+/// Fire a `CustomEvent` on the SVG with `detail_json` parsed as its `detail`
+fn dispatch_custom_event(svg: &JsValue, name: &str, detail_json: &str) {
+    let Some(target) = svg.dyn_ref::<web_sys::EventTarget>() else {
+        return;
+    };
+    let init = web_sys::CustomEventInit::new();
+    match js_sys::JSON::parse(detail_json) {
+        Ok(detail) => init.set_detail(&detail),
+        Err(err) => log::warn!("{} event detail is not JSON: {:?}", name, err),
+    }
+    match web_sys::CustomEvent::new_with_event_init_dict(name, &init) {
+        Ok(event) => {
+            let _ = target.dispatch_event(&event);
+        }
+        Err(err) => log::error!("failed to create {} event: {:?}", name, err),
+    }
+}
+
+fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) {
+    web_sys::window()
+        .expect("no window")
+        .request_animation_frame(f.as_ref().unchecked_ref())
+        .expect("should register `requestAnimationFrame`");
+}
+
+#[allow(clippy::type_complexity)]
+fn start_animation_loop(text_sphere: Rc<RefCell<TextSphere>>) {
+    let f: Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>> = Rc::new(RefCell::new(None));
+    let g = f.clone();
+
+    // Each loop gets its own generation so a stale loop exits after a resume
+    let generation = {
+        let mut ts = text_sphere.borrow_mut();
+        ts.loop_generation = ts.loop_generation.wrapping_add(1);
+        ts.last_time = 0.0;
+        ts.loop_generation
+    };
+
+    *g.borrow_mut() = Some(Closure::wrap(Box::new(move |time: f64| {
+        {
+            let mut ts = text_sphere.borrow_mut();
+            if ts.paused || ts.loop_generation != generation {
+                // Stop scheduling frames and drop our own closure to break the
+                // Rc cycle (wasm-bindgen frees it once this call returns)
+                drop(ts);
+                f.borrow_mut().take();
+                return;
+            }
+
+            let delta = if ts.last_time == 0.0 {
+                0.016 // Assume ~60fps for first frame
+            } else {
+                (time - ts.last_time) / 1000.0
+            };
+            ts.last_time = time;
+
+            ts.animate(delta);
+            let leader_lost = ts.tick_tab_sync();
+            let svg = ts.renderer.surface().clone();
+            let events = std::mem::take(&mut ts.pending_events);
+            drop(ts);
+            schedule_idle_flush(&text_sphere);
+            if leader_lost {
+                start_sync_election(&text_sphere);
+            }
+
+            // Listeners may call back into the API, so dispatch after releasing the borrow
+            for (name, detail_json) in events {
+                dispatch_custom_event(&svg, &name, &detail_json);
+            }
+        }
+
+        request_animation_frame(f.borrow().as_ref().unwrap());
+    }) as Box<dyn FnMut(f64)>));
+
+    request_animation_frame(g.borrow().as_ref().unwrap());
+}
+
+// ============================================================================
+// Idle Updates
+// ============================================================================
+
+/// A change that can wait for the browser to be idle. Positions, sizes and
+/// opacity are always applied in the frame; these only affect stacking and
+/// color, where a frame's lag is not noticeable.
+enum DeferredUpdate {
+    Reorder(Vec<CharHandle>), // Back-to-front paint order
+    UpdateColor(CharHandle, String),
+    UpdateStroke(CharHandle, f64, String), // (handle, width, color)
+}
+
+impl TextSphere {
+    /// Whether non-critical updates go to the idle queue. Only SVG benefits:
+    /// the canvas backends draw everything in `reorder`, so it can't wait.
+    /// A paused sphere has no loop to schedule the flush, so it applies
+    /// changes directly.
+    fn deferring(&self) -> bool {
+        self.defer_updates && !self.paused && self.renderer.backend() == RenderBackend::Svg
+    }
+
+    /// Queue an update for the next idle flush. Only the latest reorder is
+    /// kept, as it supersedes any earlier one.
+    fn queue_deferred(&mut self, update: DeferredUpdate) {
+        if matches!(update, DeferredUpdate::Reorder(_)) {
+            self.deferred_updates
+                .retain(|queued| !matches!(queued, DeferredUpdate::Reorder(_)));
+        }
+        self.deferred_updates.push(update);
+    }
+
+    /// Apply every queued update in order
+    fn flush_deferred_updates(&mut self) {
+        let renderer = self.renderer.as_ref();
+        for update in self.deferred_updates.drain(..) {
+            match update {
+                DeferredUpdate::Reorder(elements) => {
+                    renderer.reorder(&elements.iter().collect::<Vec<_>>());
+                }
+                DeferredUpdate::UpdateColor(handle, color) => {
+                    renderer.set_character_fill(&handle, &color);
+                }
+                DeferredUpdate::UpdateStroke(handle, width, color) => {
+                    renderer.set_character_stroke(&handle, width, &color, self.stroke_opacity);
+                }
+            }
+        }
+    }
+}
+
+/// Flush the sphere's deferred updates once the browser is idle, via
+/// `requestIdleCallback` where supported and a zero `setTimeout` otherwise.
+/// At most one flush is scheduled at a time.
+fn schedule_idle_flush(text_sphere: &Rc<RefCell<TextSphere>>) {
+    {
+        let mut ts = text_sphere.borrow_mut();
+        if ts.idle_flush_pending || ts.deferred_updates.is_empty() {
+            return;
+        }
+        ts.idle_flush_pending = true;
+    }
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+
+    let ts = text_sphere.clone();
+    let callback = Closure::once_into_js(move || {
+        let mut ts = ts.borrow_mut();
+        ts.idle_flush_pending = false;
+        ts.flush_deferred_updates();
+    });
+    let scheduled = if js_sys::Reflect::has(&window, &"requestIdleCallback".into()).unwrap_or(false)
+    {
+        let options = web_sys::IdleRequestOptions::new();
+        options.set_timeout(IDLE_FLUSH_TIMEOUT_MS);
+        window
+            .request_idle_callback_with_options(callback.unchecked_ref(), &options)
+            .is_ok()
+    } else {
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(callback.unchecked_ref(), 0)
+            .is_ok()
+    };
+    if !scheduled {
+        text_sphere.borrow_mut().idle_flush_pending = false;
+    }
+}
+
+// ============================================================================
+// Resize Handler
+// ============================================================================
+
+/// Follow the container's size: with a `ResizeObserver` where supported
+/// (so an embedded sphere tracks its card, not the window), otherwise the
+/// window's resize event. Reflows wait until resizing stops for
+/// `resize_debounce_ms`, so a drag-resize doesn't update the SVG dozens of
+/// times a second.
+fn setup_resize_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let pending: Rc<RefCell<Option<i32>>> = Rc::new(RefCell::new(None));
+    let ts = text_sphere.clone();
+    let schedule_resize = Rc::new(move |size: Option<(f64, f64)>| {
+        let window = web_sys::window().expect("no window");
+        if let Some(timeout) = pending.borrow_mut().take() {
+            window.clear_timeout_with_handle(timeout);
+        }
+
+        let delay = ts.borrow().resize_debounce_ms;
+        if delay == 0 {
+            ts.borrow_mut().resize(size);
+            return;
+        }
+        let (text_sphere, done) = (ts.clone(), pending.clone());
+        let callback = Closure::once_into_js(move || {
+            done.borrow_mut().take();
+            text_sphere.borrow_mut().resize(size);
+        });
+        *pending.borrow_mut() = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.unchecked_ref(),
+                delay.min(i32::MAX as u32) as i32,
+            )
+            .ok();
+    });
+
+    let window = web_sys::window().expect("no window");
+    let observer_supported =
+        js_sys::Reflect::has(&window, &JsValue::from_str("ResizeObserver")).unwrap_or(false);
+    if observer_supported {
+        let schedule = schedule_resize.clone();
+        let on_resize = Closure::wrap(Box::new(move |entries: js_sys::Array| {
+            // Only the latest size of the one observed element matters
+            let size = entries.iter().last().map(|entry| {
+                let rect = entry
+                    .unchecked_into::<web_sys::ResizeObserverEntry>()
+                    .content_rect();
+                (rect.width(), rect.height())
+            });
+            schedule(size);
+        }) as Box<dyn Fn(js_sys::Array)>);
+
+        match web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref()) {
+            Ok(observer) => {
+                let mut ts = text_sphere.borrow_mut();
+                observer.observe(&ts.container);
+                ts.resize_observer = Some((observer, on_resize));
+                return;
+            }
+            Err(err) => log::warn!("ResizeObserver unavailable, using window resize: {:?}", err),
+        }
+    }
+
+    let closure = Closure::wrap(Box::new(move || schedule_resize(None)) as Box<dyn Fn()>);
+    add_listener(&text_sphere, &window, &["resize"], closure);
+}
+
+// ============================================================================
+// Mouse Handler
+// ============================================================================
+
+/// Convert client (viewport) coordinates to coordinates within the SVG
+fn svg_local_point(svg: &JsValue, client_x: i32, client_y: i32) -> (f64, f64) {
+    match svg.dyn_ref::<web_sys::Element>() {
+        Some(element) => {
+            let rect = element.get_bounding_client_rect();
+            (client_x as f64 - rect.left(), client_y as f64 - rect.top())
+        }
+        None => (client_x as f64, client_y as f64),
+    }
+}
+
+fn setup_mouse_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let window = web_sys::window().expect("no window");
+    let svg: web_sys::EventTarget = text_sphere
+        .borrow()
+        .renderer
+        .surface()
+        .clone()
+        .unchecked_into();
+
+    // Drag starts on the SVG but move/up are tracked on the window so the
+    // drag keeps working when the pointer leaves the SVG
+    let ts = text_sphere.clone();
+    let on_mousedown = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        ts.borrow_mut().start_drag(event.client_x() as f64);
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+
+    let ts = text_sphere.clone();
+    let on_mousemove = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let mut ts = ts.borrow_mut();
+        ts.drag_to(event.client_x() as f64);
+
+        // Track the cursor in SVG coordinates for cursor effects
+        let (x, y) = svg_local_point(ts.renderer.surface(), event.client_x(), event.client_y());
+        ts.mouse_x = x;
+        ts.mouse_y = y;
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+
+    let ts = text_sphere.clone();
+    let on_mouseup = Closure::wrap(Box::new(move |_event: web_sys::MouseEvent| {
+        ts.borrow_mut().end_drag();
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+
+    // One delegated listener hit-tests clicks instead of one per character
+    let ts = text_sphere.clone();
+    let on_click = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let (hit, x, y) = {
+            let ts = ts.borrow();
+            let (x, y) = svg_local_point(ts.renderer.surface(), event.client_x(), event.client_y());
+            (ts.character_click(x, y), x, y)
+        };
+        // The callback may call back into the API, so the borrow is released first
+        let Some((callback, index, glyph)) = hit else {
+            return;
+        };
+        let args = js_sys::Array::of4(
+            &JsValue::from(index as u32),
+            &JsValue::from_str(&glyph),
+            &JsValue::from_f64(x),
+            &JsValue::from_f64(y),
+        );
+        if let Err(err) = callback.apply(&JsValue::NULL, &args) {
+            log::warn!("character click callback failed: {:?}", err);
+        }
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+
+    add_listener(&text_sphere, &svg, &["mousedown"], on_mousedown);
+    add_listener(&text_sphere, &svg, &["click"], on_click);
+    add_listener(&text_sphere, &window, &["mousemove"], on_mousemove);
+    add_listener(&text_sphere, &window, &["mouseup"], on_mouseup);
+}
+
+// ============================================================================
+// Touch Handler
+// ============================================================================
+
+/// A finger on the screen: its touch identifier and client coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TouchPoint {
+    id: i32,
+    x: f64,
+    y: f64,
+}
+
+fn touch_points(touches: &web_sys::TouchList) -> Vec<TouchPoint> {
+    (0..touches.length())
+        .filter_map(|i| touches.get(i))
+        .map(|touch| TouchPoint {
+            id: touch.identifier(),
+            x: touch.client_x() as f64,
+            y: touch.client_y() as f64,
+        })
+        .collect()
+}
+
+/// What the current touches ask the sphere to do
+#[derive(Debug, PartialEq)]
+enum TouchGesture {
+    None,
+    Drag { x: f64 },
+    Pinch { ids: (i32, i32), distance: f64 },
+}
+
+fn touch_distance(a: &TouchPoint, b: &TouchPoint) -> f64 {
+    (a.x - b.x).hypot(a.y - b.y)
+}
+
+/// The gesture started by `points`: a drag for one finger, a pinch for two
+fn begin_gesture(points: &[TouchPoint]) -> TouchGesture {
+    match points {
+        [] => TouchGesture::None,
+        [a] => TouchGesture::Drag { x: a.x },
+        [a, b, ..] => TouchGesture::Pinch {
+            ids: (a.id, b.id),
+            distance: touch_distance(a, b),
+        },
+    }
+}
+
+/// The gesture continued by `points` when they move. A pinch follows its two
+/// fingers by identifier, since the browser may reorder the touch list.
+fn move_gesture(points: &[TouchPoint], pinch_ids: Option<(i32, i32)>) -> TouchGesture {
+    let find = |id: i32| points.iter().find(|point| point.id == id);
+    match pinch_ids {
+        Some((id_a, id_b)) => match (find(id_a), find(id_b)) {
+            (Some(a), Some(b)) => TouchGesture::Pinch {
+                ids: (id_a, id_b),
+                distance: touch_distance(a, b),
+            },
+            _ => TouchGesture::None,
+        },
+        None => points
+            .first()
+            .map_or(TouchGesture::None, |point| TouchGesture::Drag {
+                x: point.x,
+            }),
+    }
+}
+
+/// Orbit radius after a pinch changed the finger distance by `delta`
+fn pinched_radius(radius: f64, delta: f64) -> f64 {
+    (radius + delta).clamp(PINCH_MIN_RADIUS, PINCH_MAX_RADIUS)
+}
+
+/// Start a drag (one finger) or pinch (two fingers) from the current touches
+fn begin_touch_gesture(text_sphere: &mut TextSphere, touches: &web_sys::TouchList) {
+    match begin_gesture(&touch_points(touches)) {
+        TouchGesture::Pinch { ids, distance } => text_sphere.start_pinch(ids, distance),
+        TouchGesture::Drag { x } => {
+            text_sphere.end_pinch();
+            text_sphere.start_drag(x);
+        }
+        TouchGesture::None => {
+            text_sphere.end_pinch();
+            text_sphere.end_drag();
+        }
+    }
+}
+
+fn setup_touch_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let svg: web_sys::EventTarget = text_sphere
+        .borrow()
+        .renderer
+        .surface()
+        .clone()
+        .unchecked_into();
+
+    let ts = text_sphere.clone();
+    let on_touchstart = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+        event.prevent_default();
+        begin_touch_gesture(&mut ts.borrow_mut(), &event.touches());
+    }) as Box<dyn Fn(web_sys::TouchEvent)>);
+
+    let ts = text_sphere.clone();
+    let on_touchmove = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+        event.prevent_default();
+        let mut ts = ts.borrow_mut();
+        match move_gesture(&touch_points(&event.touches()), ts.pinch_ids) {
+            TouchGesture::Pinch { distance, .. } => ts.pinch_to(distance),
+            TouchGesture::Drag { x } => ts.drag_to(x),
+            TouchGesture::None => {}
+        }
+    }) as Box<dyn Fn(web_sys::TouchEvent)>);
+
+    // Lifting a finger re-evaluates the gesture from the remaining touches,
+    // so releasing one finger of a pinch continues as a drag
+    let ts = text_sphere.clone();
+    let on_touchend = Closure::wrap(Box::new(move |event: web_sys::TouchEvent| {
+        begin_touch_gesture(&mut ts.borrow_mut(), &event.touches());
+    }) as Box<dyn Fn(web_sys::TouchEvent)>);
+
+    add_listener(&text_sphere, &svg, &["touchstart"], on_touchstart);
+    add_listener(&text_sphere, &svg, &["touchmove"], on_touchmove);
+    add_listener(
+        &text_sphere,
+        &svg,
+        &["touchend", "touchcancel"],
+        on_touchend,
+    );
+}
+
+// ============================================================================
+// Keyboard Handler
+// ============================================================================
+
+/// Whether `event` was aimed at a form field or contenteditable element,
+/// where keys belong to the user's typing rather than to the sphere
+fn is_editable_target(event: &web_sys::Event) -> bool {
+    let Some(element) = event
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+    else {
+        return false;
+    };
+    matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        || element.is_content_editable()
+}
+
+impl TextSphere {
+    /// Every instance listens on the window, so only the one under the
+    /// pointer or holding focus acts on a key
+    fn owns_keyboard(&self) -> bool {
+        self.container
+            .matches(":hover, :focus-within")
+            .unwrap_or(false)
+    }
+}
+
+fn setup_keyboard_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let ts = text_sphere.clone();
+    let on_keydown = Closure::wrap(Box::new(move |event: web_sys::KeyboardEvent| {
+        if is_editable_target(&event) || !ts.borrow().owns_keyboard() {
+            return;
+        }
+        if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("v") {
+            if !ts.borrow().clipboard_paste_enabled {
+                return;
+            }
+            let text_sphere = ts.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(err) = paste_clipboard_text(text_sphere).await {
+                    log::warn!("paste failed: {:?}", err);
+                }
+            });
+            event.prevent_default();
+            return;
+        }
+        match event.key().as_str() {
+            "ArrowLeft" => ts.borrow_mut().nudge(-KEY_NUDGE_ANGLE),
+            "ArrowRight" => ts.borrow_mut().nudge(KEY_NUDGE_ANGLE),
+            "ArrowUp" => ts.borrow_mut().change_primary_speed(KEY_SPEED_STEP),
+            "ArrowDown" => ts.borrow_mut().change_primary_speed(-KEY_SPEED_STEP),
+            " " => {
+                let resumed = {
+                    let mut ts = ts.borrow_mut();
+                    ts.paused = !ts.paused;
+                    !ts.paused
+                };
+                // The borrow must be released before the loop restarts
+                if resumed {
+                    start_animation_loop(ts.clone());
+                }
+            }
+            key => match key.parse::<u8>() {
+                Ok(digit @ 1..=9) => {
+                    ts.borrow_mut().set_primary_speed(digit as f64 * 0.1);
+                }
+                _ => return,
+            },
+        }
+        // Keep arrows and space from scrolling the page
+        event.prevent_default();
+    }) as Box<dyn Fn(web_sys::KeyboardEvent)>);
+
+    let window = web_sys::window().expect("no window");
+    add_listener(&text_sphere, &window, &["keydown"], on_keydown);
+}
+
+// ============================================================================
+// Drag and Drop
+// ============================================================================
+
+impl TextSphere {
+    /// Highlight the sphere while text is dragged over it, and restore its
+    /// colors (white in high contrast) once the drag leaves or drops
+    fn set_drop_target_active(&mut self, active: bool) {
+        if active == self.drop_target_active {
+            return;
+        }
+        self.drop_target_active = active;
+        if active {
+            let [highlight, mid, shadow] = DROP_TARGET_GRADIENT;
+            self.renderer.set_sphere_gradient(highlight, mid, shadow);
+        } else if self.high_contrast.is_some() {
+            let white = HIGH_CONTRAST_FOREGROUND;
+            self.renderer.set_sphere_gradient(white, white, white);
+        } else {
+            let [highlight, mid, shadow] = &self.sphere_gradient;
+            self.renderer.set_sphere_gradient(highlight, mid, shadow);
+        }
+    }
+}
+
+/// Replace the text with plain text dropped onto the sphere
+fn setup_drop_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let svg: web_sys::EventTarget = text_sphere
+        .borrow()
+        .renderer
+        .surface()
+        .clone()
+        .unchecked_into();
+
+    let ts = text_sphere.clone();
+    let on_dragover = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+        // Cancelling dragover is what allows a drop
+        event.prevent_default();
+        ts.borrow_mut().set_drop_target_active(true);
+    }) as Box<dyn Fn(web_sys::DragEvent)>);
+
+    let ts = text_sphere.clone();
+    let on_dragleave = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+        let mut ts = ts.borrow_mut();
+        // Moving between characters leaves one element for another inside the surface
+        let surface: &web_sys::Node = ts.renderer.surface().unchecked_ref();
+        let entered = event
+            .related_target()
+            .and_then(|target| target.dyn_into::<web_sys::Node>().ok());
+        if !surface.contains(entered.as_ref()) {
+            ts.set_drop_target_active(false);
+        }
+    }) as Box<dyn Fn(web_sys::DragEvent)>);
+
+    let ts = text_sphere.clone();
+    let on_drop = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+        // Keep the browser from opening the dropped text as a page
+        event.prevent_default();
+        let mut ts = ts.borrow_mut();
+        ts.set_drop_target_active(false);
+        let text = event
+            .data_transfer()
+            .and_then(|data| data.get_data("text/plain").ok())
+            .unwrap_or_default();
+        let text = text.trim();
+        if !text.is_empty() {
+            ts.set_text(text);
+            ts.save_config();
+        }
+    }) as Box<dyn Fn(web_sys::DragEvent)>);
+
+    add_listener(&text_sphere, &svg, &["dragover"], on_dragover);
+    add_listener(&text_sphere, &svg, &["dragleave"], on_dragleave);
+    add_listener(&text_sphere, &svg, &["drop"], on_drop);
+}
+
+// ============================================================================
+// Device Orientation
+// ============================================================================
+
+impl TextSphere {
+    /// Follow a `deviceorientation` reading: left-right tilt (`gamma`)
+    /// turns the rings and front-back tilt (`beta`) tilts them, both
+    /// smoothed by a low-pass filter. Turning is relative to the first
+    /// reading, so enabling the gyro doesn't make the text jump.
+    fn apply_device_orientation(&mut self, beta: f64, gamma: f64) {
+        let angle = gamma.clamp(-90.0, 90.0).to_radians();
+        let inclination = inclination_from_degrees(beta - GYRO_REST_BETA);
+        let (prev_angle, prev_inclination) = self.gyro_filtered.unwrap_or((angle, inclination));
+        let angle = (1.0 - GYRO_SMOOTHING) * prev_angle + GYRO_SMOOTHING * angle;
+        let inclination = (1.0 - GYRO_SMOOTHING) * prev_inclination + GYRO_SMOOTHING * inclination;
+        self.gyro_filtered = Some((angle, inclination));
+
+        for ring in &mut self.rings {
+            ring.current_angle = (ring.current_angle + angle - prev_angle).rem_euclid(TAU);
+            ring.inclination = inclination;
+            ring.tilt_target = inclination;
+        }
+        if self.paused {
+            self.animate(0.0);
+        }
+    }
+}
+
+fn setup_device_orientation_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let ts = text_sphere.clone();
+    let on_orientation = Closure::wrap(Box::new(move |event: web_sys::DeviceOrientationEvent| {
+        let mut ts = ts.borrow_mut();
+        if !ts.gyro_enabled {
+            return;
+        }
+        if let (Some(beta), Some(gamma)) = (event.beta(), event.gamma()) {
+            ts.apply_device_orientation(beta, gamma);
+        }
+    }) as Box<dyn Fn(web_sys::DeviceOrientationEvent)>);
+
+    let window = web_sys::window().expect("no window");
+    add_listener(
+        &text_sphere,
+        &window,
+        &["deviceorientation"],
+        on_orientation,
+    );
+}
+
+// ============================================================================
+// Lighting
+// ============================================================================
+
+impl TextSphere {
+    /// Whether lighting changes any color (the default ambient 1, diffuse 0
+    /// leaves them all as they are)
+    fn lighting_active(&self) -> bool {
+        self.light_ambient != 1.0 || self.light_diffuse != 0.0
+    }
+
+    /// Per-channel brightness of a character lit by the light:
+    /// `ambient + color * (diffuse * max(normal · light, 0) + rim)`. A light
+    /// behind the sphere adds a rim term to characters facing away from it.
+    fn light_tint(&self, data: &CharRenderData) -> (f64, f64, f64) {
+        let (nx, ny, nz) = data.normal;
+        let (lx, ly, lz) = self.light_direction;
+        let alignment = nx * lx + ny * ly + nz * lz;
+        let rim = if lz < 0.0 && -alignment > RIM_THRESHOLD {
+            RIM_STRENGTH * (-alignment - RIM_THRESHOLD) / (1.0 - RIM_THRESHOLD)
+        } else {
+            0.0
+        };
+        let direct = self.light_diffuse * alignment.max(0.0) + rim;
+        let (r, g, b) = self.light_color;
+        (
+            self.light_ambient + direct * r,
+            self.light_ambient + direct * g,
+            self.light_ambient + direct * b,
+        )
+    }
+
+    /// Move an orbiting light along its circle around the vertical axis
+    fn update_light(&mut self, delta: f64) {
+        if !self.light_orbiting {
+            return;
+        }
+        self.light_angle = (self.light_angle + self.light_speed * delta).rem_euclid(TAU);
+        let (sin, cos) = self.light_angle.sin_cos();
+        let radius = self.light_orbit_radius;
+        if let Some(direction) = normalize(radius * cos, LIGHT_ORBIT_HEIGHT, radius * sin) {
+            self.light_direction = direction;
+        }
+    }
+}
+
+/// `(x, y, z)` scaled to unit length, or None for the zero vector
+fn normalize(x: f64, y: f64, z: f64) -> Option<(f64, f64, f64)> {
+    let length = (x * x + y * y + z * z).sqrt();
+    (length > f64::EPSILON && length.is_finite()).then(|| (x / length, y / length, z / length))
+}
+
+// ============================================================================
+// Page Visibility
+// ============================================================================
+
+impl TextSphere {
+    /// Pause while the page is hidden, and resume once it is visible again
+    /// if it was the hiding that paused it. Returns true when the caller
+    /// must restart the animation loop.
+    fn apply_visibility(&mut self, hidden: bool) -> bool {
+        if hidden {
+            if !self.paused {
+                self.paused = true;
+                self.hidden_paused = true;
+            }
+            false
+        } else if std::mem::take(&mut self.hidden_paused) && self.paused {
+            self.paused = false;
+            // The first frame back must not see the time spent hidden
+            self.last_time = 0.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl TextSphere {
+    /// Ease down to `blur_speed_factor` of the current speed
+    fn apply_window_blur(&mut self) {
+        if self.pre_blur_speed.is_some() {
+            return;
+        }
+        let speed = self.primary_speed_target();
+        self.pre_blur_speed = Some(speed);
+        self.spin_up(speed * self.blur_speed_factor, BLUR_SPIN_DURATION);
+        self.blurred_speed = self.primary_speed_target();
+    }
+
+    /// The speed from before the blur while the blur slowdown is in effect,
+    /// or None if there is none or the speed was changed by hand since
+    fn speed_before_blur(&self) -> Option<f64> {
+        self.pre_blur_speed
+            .filter(|_| (self.primary_speed_target() - self.blurred_speed).abs() < 1e-9)
+    }
+
+    /// Ease back up to the speed from before the blur, unless the speed was
+    /// changed by hand in the meantime
+    fn apply_window_focus(&mut self) {
+        if let Some(speed) = self.speed_before_blur() {
+            self.spin_up(speed, BLUR_SPIN_DURATION);
+        }
+        self.pre_blur_speed = None;
+    }
+}
+
+/// Stop animating while the tab is in the background
+fn setup_visibility_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .expect("no document");
+    let ts = text_sphere.clone();
+    let doc = document.clone();
+    let on_change = Closure::wrap(Box::new(move || {
+        let restart = ts.borrow_mut().apply_visibility(doc.hidden());
+        // The borrow must be released before the loop restarts
+        if restart {
+            start_animation_loop(ts.clone());
+        }
+    }) as Box<dyn Fn()>);
+    add_listener(&text_sphere, &document, &["visibilitychange"], on_change);
+}
+
+/// Slow the orbit while the window doesn't have focus
+fn setup_focus_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let window = web_sys::window().expect("no window");
+    let ts = text_sphere.clone();
+    let on_blur = Closure::wrap(Box::new(move || {
+        ts.borrow_mut().apply_window_blur();
+    }) as Box<dyn Fn()>);
+    add_listener(&text_sphere, &window, &["blur"], on_blur);
+
+    let ts = text_sphere.clone();
+    let on_focus = Closure::wrap(Box::new(move || {
+        ts.borrow_mut().apply_window_focus();
+    }) as Box<dyn Fn()>);
+    add_listener(&text_sphere, &window, &["focus"], on_focus);
+}
+
+// ============================================================================
+// Reduced Motion
+// ============================================================================
+
+const REDUCED_MOTION_QUERY: &str = "(prefers-reduced-motion: reduce)";
+
+/// Whether a CSS media query currently matches
+fn media_query_matches(query: &str) -> bool {
+    web_sys::window()
+        .and_then(|w| w.match_media(query).ok().flatten())
+        .is_some_and(|list| list.matches())
+}
+
+impl TextSphere {
+    /// Hold the sphere still at angle 0 with the animation loop stopped
+    /// while reduced motion is preferred (and respected), or give back the
+    /// speed it had once that no longer applies. Returns true when the
+    /// caller must restart the animation loop.
+    fn apply_reduced_motion(&mut self, prefers_reduced: bool) -> bool {
+        let still = prefers_reduced && self.respect_reduced_motion;
+        match (still, self.reduced_motion_speed) {
+            (true, None) => {
+                self.reduced_motion_speed = Some(self.primary_speed_target());
+                self.set_primary_speed(0.0);
+                self.paused = true;
+                // Still draw one static frame
+                self.seek(0.0);
+                false
+            }
+            (false, Some(speed)) => {
+                self.reduced_motion_speed = None;
+                self.set_primary_speed(speed);
+                std::mem::replace(&mut self.paused, false)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Follow changes to the reduced-motion preference made mid-session
+fn setup_reduced_motion_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let window = web_sys::window().expect("no window");
+    let Ok(Some(query)) = window.match_media(REDUCED_MOTION_QUERY) else {
+        return;
+    };
+    let ts = text_sphere.clone();
+    let on_change = Closure::wrap(Box::new(move |event: web_sys::MediaQueryListEvent| {
+        let restart = ts.borrow_mut().apply_reduced_motion(event.matches());
+        // The borrow must be released before the loop restarts
+        if restart {
+            start_animation_loop(ts.clone());
+        }
+    }) as Box<dyn Fn(web_sys::MediaQueryListEvent)>);
+    add_listener(&text_sphere, &query, &["change"], on_change);
+}
+
+// ============================================================================
+// Character Interaction
+// ============================================================================
+
+impl TextSphere {
+    /// Listen for the pointer entering and leaving each primary-ring
+    /// character, replacing the listeners of any previous characters. Only
+    /// SVG characters are elements; ticker characters aren't tracked.
+    fn register_char_hover(&mut self) {
+        self.remove_char_hover_listeners();
+        let Some(callback) = &self.char_hover_callback else {
+            return;
+        };
+        if self.ticker_mode {
+            return;
+        }
+
+        for (index, character) in self.rings[0].characters.iter().enumerate() {
+            let Some(element) = character.element.element() else {
+                return;
+            };
+            let target: web_sys::EventTarget = element.clone().unchecked_into();
+            let node: web_sys::Node = element.clone().unchecked_into();
+            let callback = callback.clone();
+            let on_hover = Closure::wrap(Box::new(move |event: web_sys::PointerEvent| {
+                // Read the glyph now: same-length text changes reuse the element
+                let glyph = node.text_content().unwrap_or_default();
+                let entering = event.type_() == "pointerenter";
+                if let Err(err) = callback.call3(
+                    &JsValue::NULL,
+                    &JsValue::from(index as u32),
+                    &JsValue::from_str(&glyph),
+                    &JsValue::from_bool(entering),
+                ) {
+                    log::warn!("character hover callback failed: {:?}", err);
+                }
+            }) as Box<dyn Fn(web_sys::PointerEvent)>);
+            for event in ["pointerenter", "pointerleave"] {
+                let _ = target
+                    .add_event_listener_with_callback(event, on_hover.as_ref().unchecked_ref());
+            }
+            self.char_hover_listeners.push((target, on_hover));
+        }
+    }
+
+    /// The click callback and its arguments for a click at SVG
+    /// coordinates (x, y), if it landed on a primary-ring character
+    fn character_click(&self, x: f64, y: f64) -> Option<(js_sys::Function, usize, String)> {
+        let callback = self.click_callback.clone()?;
+        let index = self.character_at(x, y)?;
+        let glyph = self.rings[0].characters[index].glyph.clone();
+        Some((callback, index, glyph))
+    }
+
+    fn remove_char_hover_listeners(&mut self) {
+        for (target, on_hover) in self.char_hover_listeners.drain(..) {
+            for event in ["pointerenter", "pointerleave"] {
+                let _ = target
+                    .remove_event_listener_with_callback(event, on_hover.as_ref().unchecked_ref());
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Audio
+// ============================================================================
+
+/// A new Web Audio context, falling back to the `webkitAudioContext`
+/// constructor of older Safari
+fn create_audio_context() -> Result<web_sys::AudioContext, JsValue> {
+    if let Ok(context) = web_sys::AudioContext::new() {
+        return Ok(context);
+    }
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    let constructor: js_sys::Function =
+        js_sys::Reflect::get(&window, &"webkitAudioContext".into())?
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("Web Audio is not supported"))?;
+    Ok(js_sys::Reflect::construct(&constructor, &js_sys::Array::new())?.unchecked_into())
+}
+
+/// Play a sine tone of `freq` Hz that fades out over `duration_ms`:
+/// oscillator → gain → destination
+fn play_tone(ctx: &web_sys::AudioContext, freq: f64, duration_ms: f64) -> Result<(), JsValue> {
+    let oscillator = ctx.create_oscillator()?;
+    oscillator.frequency().set_value(freq as f32);
+
+    // An exponential fade avoids the click of an abrupt stop
+    let gain = ctx.create_gain()?;
+    let start = ctx.current_time();
+    let end = start + duration_ms.max(0.0) / 1000.0;
+    gain.gain().set_value_at_time(TONE_GAIN, start)?;
+    gain.gain().exponential_ramp_to_value_at_time(0.001, end)?;
+
+    oscillator.connect_with_audio_node(&gain)?;
+    gain.connect_with_audio_node(&ctx.destination())?;
+    oscillator.start()?;
+    oscillator.stop_with_when(end)?;
+    Ok(())
+}
+
+impl TextSphere {
+    /// Create the audio context, or wake it if the browser suspended it.
+    /// Must run inside a user gesture to be allowed to make sound.
+    fn unlock_audio(&mut self) {
+        if !self.audio_enabled {
+            return;
+        }
+        match &self.audio_context {
+            Some(context) => {
+                let _ = context.resume();
+            }
+            None => match create_audio_context() {
+                Ok(context) => self.audio_context = Some(context),
+                Err(err) => log::warn!("audio unavailable: {:?}", err),
+            },
+        }
+    }
+
+    /// Play character `index`'s tone: `freq` if given, otherwise its entry in
+    /// `char_sound_map`. Silent until the first click has created the
+    /// audio context, or while audio is disabled.
+    fn play_char_sound(&self, index: usize, freq: Option<f64>, duration_ms: f64) {
+        if !self.audio_enabled {
+            return;
+        }
+        let Some(context) = &self.audio_context else {
+            return;
+        };
+        let Some(freq) = freq.or_else(|| self.char_sound_map.get(index).copied()) else {
+            return;
+        };
+        if freq > 0.0 {
+            if let Err(err) = play_tone(context, freq, duration_ms) {
+                log::warn!("could not play tone: {:?}", err);
+            }
+        }
+    }
+}
+
+/// Start audio on the first click on the sphere (browsers only allow sound
+/// after a user gesture), and play the clicked character's tone
+fn setup_audio_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let svg: web_sys::EventTarget = text_sphere
+        .borrow()
+        .renderer
+        .surface()
+        .clone()
+        .unchecked_into();
+
+    let ts = text_sphere.clone();
+    let on_click = Closure::wrap(Box::new(move |event: web_sys::MouseEvent| {
+        let mut ts = ts.borrow_mut();
+        ts.unlock_audio();
+        let (x, y) = svg_local_point(ts.renderer.surface(), event.client_x(), event.client_y());
+        if let Some(index) = ts.character_at(x, y) {
+            ts.play_char_sound(index, None, CHAR_SOUND_DURATION_MS);
+        }
+    }) as Box<dyn Fn(web_sys::MouseEvent)>);
+    add_listener(&text_sphere, &svg, &["click"], on_click);
+}
+
+// ============================================================================
+// Microphone Input
+// ============================================================================
+
+/// A microphone stream feeding an analyser (and nothing audible)
+struct MicInput {
+    stream: web_sys::MediaStream,
+    source: web_sys::MediaStreamAudioSourceNode,
+    analyser: web_sys::AnalyserNode,
+    data: js_sys::Float32Array, // Reused for every getFloatFrequencyData call
+    spectrum: Vec<f32>,         // dB per frequency bin, copied out of `data`
+    level: f64,                 // Overall loudness, 0 (quiet) to 1 (loud)
+    band_levels: Vec<f64>,      // Loudness (0-1) of each equal slice of the spectrum
+}
+
+impl MicInput {
+    /// Loudness (0-1) of bins `bins` on the analyser's decibel scale: the
+    /// RMS of their linear magnitudes
+    fn loudness(&self, bins: std::ops::Range<usize>) -> f64 {
+        let bins = &self.spectrum[bins];
+        let mean_square = bins
+            .iter()
+            .map(|&db| 10f64.powf(db as f64 / 10.0))
+            .sum::<f64>()
+            / bins.len().max(1) as f64;
+        let (min_db, max_db) = (self.analyser.min_decibels(), self.analyser.max_decibels());
+        let rms_db = 10.0 * mean_square.log10();
+        ((rms_db - min_db) / (max_db - min_db)).clamp(0.0, 1.0)
+    }
+}
+
+impl TextSphere {
+    /// Read the latest spectrum and return the rotation speed multiplier
+    /// for its loudness (1 without microphone input)
+    fn update_mic_input(&mut self) -> f64 {
+        let Some(mic) = &mut self.mic_input else {
+            return 1.0;
+        };
+        read_frequency_data(&mic.analyser, &mic.data);
+        mic.data.copy_to(&mut mic.spectrum);
+        mic.level = mic.loudness(0..mic.spectrum.len());
+
+        let bins = mic.spectrum.len();
+        let bands = self.frequency_bands.min(bins);
+        mic.band_levels.clear();
+        for band in 0..bands {
+            let level = mic.loudness(band * bins / bands..(band + 1) * bins / bands);
+            mic.band_levels.push(level);
+        }
+        MIC_MIN_SPEED_FACTOR + (MIC_MAX_SPEED_FACTOR - MIC_MIN_SPEED_FACTOR) * mic.level
+    }
+
+    /// Stop the microphone and disconnect the analyser
+    fn close_mic_input(&mut self) {
+        let Some(mic) = self.mic_input.take() else {
+            return;
+        };
+        for track in mic.stream.get_tracks().iter() {
+            track.unchecked_into::<web_sys::MediaStreamTrack>().stop();
+        }
+        let _ = mic.source.disconnect();
+    }
+}
+
+/// Ask for the microphone and start analysing it. The audio context is
+/// created here if a click hasn't created it already.
+async fn open_mic_input(text_sphere: Rc<RefCell<TextSphere>>) -> Result<(), JsValue> {
+    let navigator = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no window"))?
+        .navigator();
+    let media_devices = js_sys::Reflect::get(&navigator, &"mediaDevices".into())?;
+    if media_devices.is_undefined() {
+        log::warn!("microphone input is not supported in this browser");
+        return Err(JsValue::from_str("microphone input is not supported"));
+    }
+    let media_devices: web_sys::MediaDevices = media_devices.unchecked_into();
+    let constraints = web_sys::MediaStreamConstraints::new();
+    constraints.set_audio(&JsValue::TRUE);
+    let stream: web_sys::MediaStream =
+        JsFuture::from(media_devices.get_user_media_with_constraints(&constraints)?)
+            .await?
+            .unchecked_into();
+
+    let mut ts = text_sphere.borrow_mut();
+    let context = match &ts.audio_context {
+        Some(context) => context.clone(),
+        None => create_audio_context()?,
+    };
+    // Past the await this no longer counts as a user gesture, so a new
+    // context can start suspended; the analyser reads silence until resumed
+    let _ = context.resume();
+    ts.audio_context = Some(context.clone());
+    let source = context.create_media_stream_source(&stream)?;
+    let analyser = context.create_analyser()?;
+    analyser.set_fft_size(MIC_FFT_SIZE);
+    source.connect_with_audio_node(&analyser)?;
+
+    let bins = analyser.frequency_bin_count();
+    ts.close_mic_input();
+    ts.mic_input = Some(MicInput {
+        stream,
+        source,
+        analyser,
+        data: js_sys::Float32Array::new_with_length(bins),
+        spectrum: vec![0.0; bins as usize],
+        level: 0.0,
+        band_levels: Vec::new(),
+    });
+    Ok(())
+}
+
+// ============================================================================
+// Accessibility
+// ============================================================================
+
+/// Visually hidden `aria-live` span in the container through which screen
+/// readers announce the text (the orbiting characters themselves are not
+/// readable in order)
+fn create_live_region(container: &web_sys::Element) -> Result<web_sys::HtmlElement, JsValue> {
+    let document = container
+        .owner_document()
+        .ok_or_else(|| JsValue::from_str("container is not in a document"))?;
+    let region: web_sys::HtmlElement = document.create_element("span")?.dyn_into()?;
+    region.set_id(&format!("{}-live-region", container.id()));
+    region.set_attribute("aria-live", "polite")?;
+    region.set_attribute("aria-label", "Sphere text")?;
+    region.style().set_css_text(
+        "position: absolute; width: 1px; height: 1px; overflow: hidden; \
+         clip: rect(0 0 0 0); white-space: nowrap;",
+    );
+    container.append_child(&region)?;
+    Ok(region)
+}
+
+impl TextSphere {
+    /// Label of the drawing surface: the host's override, or one naming the text
+    fn aria_label(&self) -> String {
+        self.aria_label_override
+            .clone()
+            .unwrap_or_else(|| format!("Animated text sphere displaying: {}", self.rings[0].text))
+    }
+
+    /// Sync the live region and the surface's label with the primary text.
+    /// The surface is a single image to screen readers (`role="img"`), so
+    /// its individual characters are never read out.
+    fn update_accessible_text(&self) {
+        let text = &self.rings[0].text;
+        if self.live_region.text_content().as_deref() != Some(text.as_str()) {
+            self.live_region.set_text_content(Some(text));
+        }
+        if let Some(surface) = self.renderer.surface().dyn_ref::<web_sys::Element>() {
+            let _ = surface.set_attribute("role", "img");
+            let _ = surface.set_attribute("aria-label", &self.aria_label());
+        }
+    }
+}
+
+const HIGH_CONTRAST_QUERY: &str = "(prefers-contrast: more)";
+
+/// Settings high-contrast mode overrides, put back when it is turned off
+struct ContrastRestore {
+    depth_fade: bool,
+    depth_color_blend: f64,
+    glow_enabled: bool,
+    background_color: String,
+    highlight_color: String,
+}
+
+impl TextSphere {
+    /// White characters at full opacity and a white sphere outlined in
+    /// black, on black. Depth fade, depth color and glow are switched off
+    /// meanwhile; turning the mode off restores them.
+    fn set_high_contrast(&mut self, enabled: bool) {
+        match (enabled, self.high_contrast.take()) {
+            (true, None) => {
+                self.high_contrast = Some(ContrastRestore {
+                    depth_fade: self.depth_fade,
+                    depth_color_blend: self.depth_color_blend,
+                    glow_enabled: self.glow_enabled,
+                    background_color: self.background_color.clone(),
+                    highlight_color: self.highlight_color.clone(),
+                });
+                self.depth_fade = false;
+                self.depth_color_blend = 0.0;
+                self.set_glow_enabled(false);
+                self.set_background(Some(HIGH_CONTRAST_BACKGROUND.to_string()));
+                self.highlight_color = HIGH_CONTRAST_FOREGROUND.to_string();
+                self.renderer.set_sphere_gradient(
+                    HIGH_CONTRAST_FOREGROUND,
+                    HIGH_CONTRAST_FOREGROUND,
+                    HIGH_CONTRAST_FOREGROUND,
+                );
+                self.renderer
+                    .set_sphere_outline(HIGH_CONTRAST_BACKGROUND, HIGH_CONTRAST_OUTLINE);
+            }
+            (false, Some(saved)) => {
+                self.depth_fade = saved.depth_fade;
+                self.depth_color_blend = saved.depth_color_blend;
+                self.set_glow_enabled(saved.glow_enabled);
+                self.set_background(
+                    (!saved.background_color.is_empty()).then_some(saved.background_color),
+                );
+                self.highlight_color = saved.highlight_color;
+                let [highlight, mid, shadow] = &self.sphere_gradient;
+                self.renderer.set_sphere_gradient(highlight, mid, shadow);
+                self.renderer.set_sphere_outline("", 0.0);
+            }
+            (_, unchanged) => {
+                self.high_contrast = unchanged;
+                return;
+            }
+        }
+        self.colors_dirty = true;
+        // A paused sphere (e.g. for reduced motion) would otherwise keep the old colors
+        if self.paused {
+            self.animate(0.0);
+        }
+    }
+}
+
+/// Follow the OS `prefers-contrast: more` setting, including mid-session changes
+fn setup_high_contrast_handler(text_sphere: Rc<RefCell<TextSphere>>) {
+    let window = web_sys::window().expect("no window");
+    let Ok(Some(query)) = window.match_media(HIGH_CONTRAST_QUERY) else {
+        return;
+    };
+    let ts = text_sphere.clone();
+    let on_change = Closure::wrap(Box::new(move |event: web_sys::MediaQueryListEvent| {
+        ts.borrow_mut().set_high_contrast(event.matches());
+    }) as Box<dyn Fn(web_sys::MediaQueryListEvent)>);
+    add_listener(&text_sphere, &query, &["change"], on_change);
+}
+
+// ============================================================================
+// Ticker
+// ============================================================================
+
+impl TextSphere {
+    /// Switch ring 0 between showing its text and streaming queued
+    /// characters past the front like a news ticker. The current text seeds
+    /// the ticker when it starts.
+    fn set_ticker_mode(&mut self, enabled: bool) {
+        if enabled == self.ticker_mode {
+            return;
+        }
+        for character in std::mem::take(&mut self.rings[0].characters) {
+            self.recycle_character(character);
+        }
+        self.ticker_mode = enabled;
+        self.register_char_hover();
+
+        let text = self.rings[0].text.clone();
+        if enabled {
+            self.push_ticker_text(&text);
+        } else {
+            self.ticker_queue.clear();
+            for element in self.free_list.drain(..) {
+                self.renderer.remove(&element);
+            }
+            self.set_text(&text);
+        }
+    }
+
+    /// Queue text to enter the ticker, each character a step further round
+    /// the hue wheel. Runs of whitespace become a single blank gap.
+    fn push_ticker_text(&mut self, text: &str) {
+        let mut gap = self
+            .ticker_queue
+            .back()
+            .is_some_and(|(glyph, _)| glyph == " ");
+        for cluster in text.graphemes(true) {
+            let is_space = cluster.chars().all(char::is_whitespace);
+            if is_space && gap {
+                continue;
+            }
+            gap = is_space;
+            let glyph = if is_space { " " } else { cluster };
+            let color = get_color_for_hue(self.ticker_hue, 1.0);
+            self.ticker_hue = (self.ticker_hue + TICKER_HUE_STEP).rem_euclid(360.0);
+            self.ticker_queue.push_back((glyph.to_string(), color));
+        }
+    }
+
+    /// Advance ticker characters with the rotation: those that have covered
+    /// TICKER_TRAVEL slide off the front, and queued ones enter behind the
+    /// newest as it clears the way
+    fn update_ticker(&mut self, delta: f64) {
+        if !self.ticker_mode {
+            return;
+        }
+        let ring = &mut self.rings[0];
+        if !self.drag_active {
+            let travel = (ring.rotation_speed * delta).abs();
+            for character in &mut ring.characters {
+                character.ticker_travel += travel;
+            }
+        }
+
+        while self.rings[0]
+            .characters
+            .first()
+            .is_some_and(|character| character.ticker_travel >= TICKER_TRAVEL)
+        {
+            let character = self.rings[0].characters.remove(0);
+            self.recycle_character(character);
+        }
+
+        while let Some((glyph, color)) = self.ticker_queue.pop_front() {
+            let ring = &self.rings[0];
+            let direction = if ring.rotation_speed < 0.0 { -1.0 } else { 1.0 };
+            let width = width_factor(&glyph);
+            // Enter one slot behind the newest character, keeping the
+            // distance it has travelled past its own slot
+            let (base_angle, travel) = match ring.characters.last() {
+                Some(newest) => {
+                    let gap =
+                        TICKER_SLOT * ring.letter_spacing * (newest.width_factor + width) / 2.0;
+                    if newest.ticker_travel < gap {
+                        self.ticker_queue.push_front((glyph, color));
+                        break;
+                    }
+                    (
+                        newest.base_angle - direction * gap,
+                        newest.ticker_travel - gap,
+                    )
+                }
+                None => (
+                    ring.start_angle - direction * TICKER_TRAVEL / 2.0 - ring.current_angle,
+                    0.0,
+                ),
+            };
+            self.spawn_ticker_character(glyph, color, base_angle, travel);
+        }
+    }
+
+    /// Add a character to the end of ring 0, reusing a freed handle if
+    /// one is available
+    fn spawn_ticker_character(
+        &mut self,
+        glyph: String,
+        color: String,
+        base_angle: f64,
+        travel: f64,
+    ) {
+        let renderer = self.renderer.as_ref();
+        let element = match self.free_list.pop() {
+            Some(element) => {
+                renderer.set_character_text(&element, &glyph);
+                element
+            }
+            None => renderer.create_character(
+                &glyph,
+                &color,
+                &Placement {
+                    x: self.center_x,
+                    y: self.center_y,
+                    ..Placement::HIDDEN
+                },
+            ),
+        };
+        renderer.set_character_fill(&element, &color);
+        renderer.set_character_font(&element, &self.font_family, &self.font_weight);
+        renderer.set_character_stroke(
+            &element,
+            self.stroke_width,
+            &self.stroke_color,
+            self.stroke_opacity,
+        );
+        self.apply_text_shadow(&element, 1.0);
+
+        self.rings[0].characters.push(Character {
+            element,
+            width_factor: width_factor(&glyph),
+            glyph,
+            base_angle,
+            base_hue: 0.0,
+            glow_filter: None,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            vel_x: 0.0,
+            vel_y: 0.0,
+            highlighted: false,
+            highlight_scale: 1.0,
+            highlight_vel: 0.0,
+            trail: VecDeque::new(),
+            frozen: None,
+            custom_color: Some(color),
+            ticker_travel: travel,
+            orbit_offset: 0.0,
+            spring_to_base: None,
+            ghosts: Vec::new(),
+        });
+    }
+
+    /// Hide a departed character's handle and keep it for reuse; its glow
+    /// filter and trail ghosts are removed
+    fn recycle_character(&mut self, character: Character) {
+        self.renderer
+            .update_character(&character.element, &Placement::HIDDEN);
+        if let Some(filter) = &character.glow_filter {
+            if let Some(element) = character.element.element() {
+                set_element_filter(element, "");
+            }
+            remove_element(filter);
+        }
+        for ghost in &character.ghosts {
+            self.renderer.remove(ghost);
+        }
+        self.free_list.push(character.element);
+    }
+}
+
+// ============================================================================
+// Text Feed
+// ============================================================================
+
+impl TextSphere {
+    /// Close the feed socket and cancel any pending reconnect
+    fn close_text_feed(&mut self) {
+        self.text_feed_url = None;
+        if let Some(timer) = self.text_feed_retry_timer.take() {
+            if let Some(window) = web_sys::window() {
+                window.clear_timeout_with_handle(timer);
+            }
+        }
+        if let Some(socket) = self.text_feed.take() {
+            // Detach first so closing doesn't schedule a reconnect
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onerror(None);
+            socket.set_onclose(None);
+            let _ = socket.close();
+        }
+        self.text_feed_handlers.clear();
+    }
+}
+
+/// Open a socket to the sphere's `text_feed_url`, replacing the text with
+/// each text message received
+fn open_text_feed(text_sphere: &Rc<RefCell<TextSphere>>) -> Result<(), JsValue> {
+    let (url, svg) = {
+        let ts = text_sphere.borrow();
+        match &ts.text_feed_url {
+            Some(url) => (url.clone(), ts.renderer.surface().clone()),
+            None => return Ok(()),
+        }
+    };
+    let socket = web_sys::WebSocket::new(&url)?;
+
+    let handle = text_sphere.clone();
+    let on_open = Closure::wrap(Box::new(move || {
+        handle.borrow_mut().text_feed_retry_delay = FEED_RETRY_DELAY;
+    }) as Box<dyn FnMut()>);
+
+    let handle = text_sphere.clone();
+    let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        // Binary frames arrive as a Blob or ArrayBuffer and are ignored
+        if let Some(text) = event.data().as_string() {
+            handle.borrow_mut().set_text(text.trim());
+        }
+    }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+
+    let on_error = Closure::wrap(Box::new(move |event: web_sys::Event| {
+        let detail = serde_json::json!({ "url": url, "type": event.type_() });
+        dispatch_custom_event(&svg, "text-feed-error", &detail.to_string());
+    }) as Box<dyn FnMut(web_sys::Event)>);
+
+    let handle = text_sphere.clone();
+    let on_close = Closure::wrap(Box::new(move || {
+        handle.borrow_mut().text_feed = None;
+        schedule_text_feed_retry(&handle);
+    }) as Box<dyn FnMut()>);
+
+    socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+    socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+    socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+    socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+    let mut ts = text_sphere.borrow_mut();
+    ts.text_feed = Some(socket);
+    ts.text_feed_handlers = vec![
+        Box::new(on_open),
+        Box::new(on_message),
+        Box::new(on_error),
+        Box::new(on_close),
+    ];
+    Ok(())
+}
+
+/// Reconnect after the current backoff delay, doubling it (up to
+/// MAX_FEED_RETRY_DELAY) for the attempt after
+fn schedule_text_feed_retry(text_sphere: &Rc<RefCell<TextSphere>>) {
+    let mut ts = text_sphere.borrow_mut();
+    if ts.text_feed_url.is_none() || ts.text_feed_retry_timer.is_some() {
+        return;
+    }
+    let delay = ts.text_feed_retry_delay;
+    ts.text_feed_retry_delay = (delay * 2.0).min(MAX_FEED_RETRY_DELAY);
+
+    let handle = text_sphere.clone();
+    let retry = Closure::once_into_js(move || {
+        handle.borrow_mut().text_feed_retry_timer = None;
+        if let Err(err) = open_text_feed(&handle) {
+            log::warn!("text feed reconnect failed: {:?}", err);
+            schedule_text_feed_retry(&handle);
+        }
+    });
+    let timer = web_sys::window().and_then(|window| {
+        window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                retry.unchecked_ref(),
+                (delay * 1000.0) as i32,
+            )
+            .ok()
+    });
+    ts.text_feed_retry_timer = timer;
+}
+
+// ============================================================================
+// Tab Sync
+// ============================================================================
+
+#[derive(Clone, Copy, PartialEq)]
+enum SyncRole {
+    Electing, // Claimed leadership and waiting for objections
+    Leader,   // Broadcasting its angle
+    Follower, // Mirroring the leader's angle
+}
+
+/// Membership of a BroadcastChannel shared by every tab showing the sphere.
+/// One tab leads and broadcasts its rotation; the rest follow it.
+struct TabSync {
+    channel: web_sys::BroadcastChannel,
+    tab_id: f64, // Random id; on a tie the lower id keeps the lead
+    role: SyncRole,
+    last_message: f64, // Date.now() of the last angle sent (leader) or received
+    election: u32,     // Incremented per election so stale timers are ignored
+    own_speed: Option<f64>, // Primary ring speed to restore when no longer following
+    _on_message: Closure<dyn FnMut(web_sys::MessageEvent)>,
+}
+
+impl TabSync {
+    fn post(&self, kind: &str, angle: Option<(f64, f64)>) {
+        let message = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&message, &"type".into(), &kind.into());
+        let _ = js_sys::Reflect::set(&message, &"from".into(), &self.tab_id.into());
+        if let Some((value, speed)) = angle {
+            let _ = js_sys::Reflect::set(&message, &"value".into(), &value.into());
+            let _ = js_sys::Reflect::set(&message, &"speed".into(), &speed.into());
+        }
+        if let Err(err) = self.channel.post_message(&message) {
+            log::warn!("tab sync: failed to post {}: {:?}", kind, err);
+        }
+    }
+}
+
+impl TextSphere {
+    /// Leave the sync channel, handing the lead to another tab and going
+    /// back to rotating at this tab's own speed
+    fn close_tab_sync(&mut self) {
+        let Some(sync) = self.tab_sync.take() else {
+            return;
+        };
+        if sync.role == SyncRole::Leader {
+            sync.post("resign", None);
+        }
+        sync.channel.set_onmessage(None);
+        sync.channel.close();
+        if let Some(speed) = sync.own_speed {
+            self.set_primary_speed(speed);
+        }
+    }
+
+    fn become_sync_leader(&mut self) {
+        let Some(sync) = &mut self.tab_sync else {
+            return;
+        };
+        sync.role = SyncRole::Leader;
+        sync.last_message = 0.0; // Broadcast on the next frame
+        sync.post("ack_leader", None);
+        if let Some(speed) = sync.own_speed.take() {
+            self.set_primary_speed(speed);
+        }
+    }
+
+    fn become_sync_follower(&mut self) {
+        let own_speed = self.primary_speed_target();
+        let Some(sync) = &mut self.tab_sync else {
+            return;
+        };
+        if sync.role != SyncRole::Follower {
+            sync.role = SyncRole::Follower;
+            sync.own_speed.get_or_insert(own_speed);
+        }
+        sync.last_message = js_sys::Date::now();
+    }
+
+    /// Handle a message from another tab. Returns true when the leader has
+    /// resigned and a new election should start.
+    fn receive_sync_message(&mut self, data: &JsValue) -> bool {
+        let field = |name: &str| js_sys::Reflect::get(data, &name.into()).ok();
+        let kind = field("type")
+            .and_then(|v| v.as_string())
+            .unwrap_or_default();
+        let Some(from) = field("from").and_then(|v| v.as_f64()) else {
+            return false;
+        };
+        let Some(sync) = &self.tab_sync else {
+            return false;
+        };
+        let (role, tab_id) = (sync.role, sync.tab_id);
+
+        match kind.as_str() {
+            "claim_leader" if role == SyncRole::Leader => sync.post("ack_leader", None),
+            // Two leaders can appear when elections overlap; the lower id wins
+            "ack_leader" if role != SyncRole::Leader || from < tab_id => {
+                self.become_sync_follower();
+            }
+            "angle" => {
+                if role == SyncRole::Leader && from > tab_id {
+                    return false;
+                }
+                let (Some(value), Some(speed)) = (
+                    field("value").and_then(|v| v.as_f64()),
+                    field("speed").and_then(|v| v.as_f64()),
+                ) else {
+                    return false;
+                };
+                self.become_sync_follower();
+                self.set_primary_speed(speed);
+                self.seek(value);
+            }
+            "resign" => return role == SyncRole::Follower,
+            _ => {}
+        }
+        false
+    }
+
+    /// Per-frame sync work: the leader broadcasts its angle every
+    /// SYNC_INTERVAL_MS. Returns true when a follower has stopped hearing
+    /// from the leader and a new election should start.
+    fn tick_tab_sync(&mut self) -> bool {
+        let angle = self.rings[0].current_angle;
+        let speed = self.rings[0].rotation_speed;
+        let Some(sync) = &mut self.tab_sync else {
+            return false;
+        };
+        let now = js_sys::Date::now();
+        match sync.role {
+            SyncRole::Leader if now - sync.last_message >= SYNC_INTERVAL_MS => {
+                sync.last_message = now;
+                sync.post("angle", Some((angle, speed)));
+                false
+            }
+            SyncRole::Follower => now - sync.last_message >= SYNC_LEADER_TIMEOUT_MS,
+            _ => false,
+        }
+    }
+}
+
+/// Claim leadership of the sync channel. The claim succeeds unless the
+/// current leader acknowledges itself within SYNC_ELECTION_MS.
+fn start_sync_election(text_sphere: &Rc<RefCell<TextSphere>>) {
+    let election = {
+        let mut ts = text_sphere.borrow_mut();
+        let Some(sync) = &mut ts.tab_sync else {
+            return;
+        };
+        sync.role = SyncRole::Electing;
+        sync.election = sync.election.wrapping_add(1);
+        sync.last_message = js_sys::Date::now();
+        sync.post("claim_leader", None);
+        sync.election
+    };
+
+    let handle = text_sphere.clone();
+    let decide = Closure::once_into_js(move || {
+        let mut ts = handle.borrow_mut();
+        let undisputed = ts
+            .tab_sync
+            .as_ref()
+            .is_some_and(|sync| sync.role == SyncRole::Electing && sync.election == election);
+        if undisputed {
+            ts.become_sync_leader();
+        }
+    });
+    if let Some(window) = web_sys::window() {
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            decide.unchecked_ref(),
+            SYNC_ELECTION_MS,
+        );
+    }
+}
+
+/// Join `channel_name` and elect a leader among the tabs on it
+fn open_tab_sync(text_sphere: &Rc<RefCell<TextSphere>>, channel_name: &str) -> Result<(), JsValue> {
+    let channel = web_sys::BroadcastChannel::new(channel_name)?;
+
+    let handle = text_sphere.clone();
+    let on_message = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
+        let leader_lost = handle.borrow_mut().receive_sync_message(&event.data());
+        if leader_lost {
+            start_sync_election(&handle);
+        }
+    }) as Box<dyn FnMut(web_sys::MessageEvent)>);
+    channel.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+    {
+        let mut ts = text_sphere.borrow_mut();
+        ts.close_tab_sync();
+        ts.tab_sync = Some(TabSync {
+            channel,
+            tab_id: js_sys::Math::random(),
+            role: SyncRole::Electing,
+            last_message: 0.0,
+            election: 0,
+            own_speed: None,
+            _on_message: on_message,
+        });
+    }
+    start_sync_election(text_sphere);
+    Ok(())
+}
+
+// ============================================================================
+// Text Fetch
+// ============================================================================
+
+/// Why `fetch_text` failed, reported in its `text-fetch-error` event
+enum FetchError {
+    Timeout,
+    Cors,    // Server reachable, but the response isn't shared cross-origin
+    Network, // Server unreachable (or offline)
+    Status(u16),
+    Other(String),
+}
+
+impl From<JsValue> for FetchError {
+    fn from(err: JsValue) -> Self {
+        FetchError::Other(format!("{:?}", err))
+    }
+}
+
+impl FetchError {
+    /// `{url, kind, message}` detail of the error event
+    fn detail_json(&self, url: &str) -> String {
+        let (kind, message) = match self {
+            FetchError::Timeout => (
+                "timeout",
+                format!("no response within {} ms", FETCH_TIMEOUT_MS),
+            ),
+            FetchError::Cors => (
+                "cors",
+                "the server does not allow cross-origin requests".to_string(),
+            ),
+            FetchError::Network => ("network", "the server could not be reached".to_string()),
+            FetchError::Status(status) => ("http", format!("server responded with {}", status)),
+            FetchError::Other(message) => ("other", message.clone()),
+        };
+        serde_json::json!({ "url": url, "kind": kind, "message": message }).to_string()
+    }
+}
+
+/// Fetch `url` as text, aborting after FETCH_TIMEOUT_MS
+async fn fetch_plain_text(url: &str) -> Result<String, FetchError> {
+    let window = web_sys::window().ok_or_else(|| FetchError::Other("no window".to_string()))?;
+    let controller = web_sys::AbortController::new()?;
+    let signal = controller.signal();
+    let abort = Closure::once_into_js(move || controller.abort());
+    let timer = window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            abort.unchecked_ref(),
+            FETCH_TIMEOUT_MS,
+        )
+        .ok();
+
+    let result = fetch_with_signal(&window, url, &signal).await;
+    if let Some(timer) = timer {
+        window.clear_timeout_with_handle(timer);
+    }
+    result
+}
+
+async fn fetch_with_signal(
+    window: &web_sys::Window,
+    url: &str,
+    signal: &web_sys::AbortSignal,
+) -> Result<String, FetchError> {
+    let init = web_sys::RequestInit::new();
+    init.set_signal(Some(signal));
+    let response = match JsFuture::from(window.fetch_with_str_and_init(url, &init)).await {
+        Ok(response) => response,
+        Err(_) if signal.aborted() => return Err(FetchError::Timeout),
+        // CORS and network failures both reject with a bare TypeError
+        Err(_) => {
+            let reachable = reachable_without_cors(window, url, signal).await;
+            return Err(match reachable {
+                _ if signal.aborted() => FetchError::Timeout,
+                true => FetchError::Cors,
+                false => FetchError::Network,
+            });
+        }
+    };
+
+    let response: web_sys::Response = response.dyn_into()?;
+    if !response.ok() {
+        return Err(FetchError::Status(response.status()));
+    }
+    let body = JsFuture::from(response.text()?)
+        .await
+        .map_err(|err| match signal.aborted() {
+            true => FetchError::Timeout,
+            false => FetchError::from(err),
+        })?;
+    body.as_string()
+        .ok_or_else(|| FetchError::Other("response body is not text".to_string()))
+}
+
+/// A request that fails normally but succeeds as an opaque `no-cors`
+/// request was blocked by CORS rather than the network
+async fn reachable_without_cors(
+    window: &web_sys::Window,
+    url: &str,
+    signal: &web_sys::AbortSignal,
+) -> bool {
+    let init = web_sys::RequestInit::new();
+    init.set_mode(web_sys::RequestMode::NoCors);
+    init.set_signal(Some(signal));
+    JsFuture::from(window.fetch_with_str_and_init(url, &init))
+        .await
+        .is_ok()
+}
+
+/// Read the clipboard's text and show up to `max_clipboard_chars` of it,
+/// returning the text that was set. Fails with the browser's DOMException
+/// when clipboard access is denied, and leaves the text alone when the
+/// clipboard holds no text or only whitespace.
+async fn paste_clipboard_text(text_sphere: Rc<RefCell<TextSphere>>) -> Result<String, JsValue> {
+    let navigator = web_sys::window()
+        .ok_or_else(|| JsValue::from_str("no window"))?
+        .navigator();
+    let clipboard = js_sys::Reflect::get(&navigator, &"clipboard".into())?;
+    let read_text: js_sys::Function = js_sys::Reflect::get(&clipboard, &"readText".into())
+        .ok()
+        .and_then(|read_text| read_text.dyn_into().ok())
+        .ok_or_else(|| JsValue::from_str("clipboard reading is not supported"))?;
+    let promise: js_sys::Promise = read_text.call0(&clipboard)?.dyn_into()?;
+    let text = JsFuture::from(promise)
+        .await?
+        .as_string()
+        .unwrap_or_default();
+
+    let mut ts = text_sphere.borrow_mut();
+    let text: String = text
+        .trim()
+        .graphemes(true)
+        .take(ts.max_clipboard_chars)
+        .collect();
+    if text.is_empty() {
+        return Err(JsValue::from_str("clipboard has no text"));
+    }
+    ts.set_text(&text);
+    ts.save_config();
+    Ok(text)
+}
+
+// ============================================================================
+// WASM API
+// ============================================================================
+
+/// Replace the orbiting text at runtime (spaces are skipped)
+#[wasm_bindgen]
+pub fn set_text(instance_id: u32, text: &str) {
+    if with_text_sphere_saved(instance_id, |ts| ts.set_text(text)).is_none() {
+        log::warn!("set_text called before the sphere was initialized");
+    }
+}
+
+/// Cross-fade to `text` over `duration_ms`: each old character fades out in
+/// place while the new text fades in. Applies immediately while paused.
+#[wasm_bindgen]
+pub fn morph_text(instance_id: u32, text: &str, duration_ms: f64) {
+    if with_text_sphere_saved(instance_id, |ts| ts.morph_text(text, duration_ms / 1000.0)).is_none()
+    {
+        log::warn!("morph_text called before the sphere was initialized");
+    }
+}
+
+/// Stop the animation loop; no further frames are scheduled until `resume()`
+#[wasm_bindgen]
+pub fn pause(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| {
+        ts.paused = true;
+        // Stay paused when a hidden page becomes visible again
+        ts.hidden_paused = false;
+    });
+}
+
+/// Restart the animation loop after `pause()`
+#[wasm_bindgen]
+pub fn resume(instance_id: u32) {
+    if let Some(text_sphere) = text_sphere_handle(instance_id) {
+        let was_paused = std::mem::replace(&mut text_sphere.borrow_mut().paused, false);
+        if was_paused {
+            start_animation_loop(text_sphere);
+        }
+    }
+}
+
+/// Make characters bob up and down as they orbit (amplitude 0 = flat orbit)
+#[wasm_bindgen]
+pub fn set_vertical_wave(instance_id: u32, amplitude: f64, frequency: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.vertical_amplitude = amplitude;
+        ts.vertical_frequency = frequency;
+    });
+}
+
+/// Tilt the orbit plane (0° = flat, 90° = vertical)
+#[wasm_bindgen]
+pub fn set_inclination(instance_id: u32, degrees: f64) {
+    let inclination = inclination_from_degrees(degrees);
+    with_text_sphere_saved(instance_id, |ts| {
+        for ring in &mut ts.rings {
+            ring.inclination = inclination;
+            ring.tilt_target = inclination;
+        }
+    });
+}
+
+/// Set rotation speed in rad/s; negative reverses, zero freezes the orbit
+#[wasm_bindgen]
+pub fn set_rotation_speed(instance_id: u32, speed: f64) {
+    if !speed.is_finite() {
+        log::warn!("set_rotation_speed: ignoring non-finite speed {}", speed);
+        return;
+    }
+    let clamped = speed.clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+    if clamped != speed {
+        log::warn!(
+            "rotation speed {} out of range, clamped to {}",
+            speed,
+            clamped
+        );
+    }
+    with_text_sphere_saved(instance_id, |ts| ts.set_primary_speed(clamped));
+}
+
+/// Enable or disable fading of characters behind the sphere
+#[wasm_bindgen]
+pub fn set_depth_fade(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| ts.depth_fade = enabled);
+}
+
+/// Choose how opacity varies with depth, by `OpacityCurveMode`: constant,
+/// linear (default), cosine, squared, or a step that hides characters at or
+/// below depth `param` (-1 = back, 1 = front). Constant turns depth fade off;
+/// the others turn it on.
+#[wasm_bindgen]
+pub fn set_opacity_curve(instance_id: u32, mode: u8, param: f64) -> Result<(), JsValue> {
+    let curve = OpacityCurve::from_mode(mode, param)
+        .ok_or_else(|| JsValue::from_str(&format!("unknown opacity curve: {}", mode)))?;
+    with_text_sphere(instance_id, |ts| {
+        ts.opacity_curve = curve;
+        ts.depth_fade = curve != OpacityCurve::Constant;
+    });
+    Ok(())
+}
+
+/// Set the opacity of the back-most character when depth fade is enabled
+#[wasm_bindgen]
+pub fn set_min_opacity(instance_id: u32, v: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.min_opacity = v.clamp(0.0, 1.0));
+}
+
+/// Add a ring of `text` orbiting at `radius` pixels (clamped to 10–1000) and
+/// `speed` rad/s. Returns the new ring's id, or `u32::MAX` if the sphere is
+/// not running or `speed` is not finite. The id keeps referring to the ring
+/// when other rings are removed; the primary ring's id is 0.
+#[wasm_bindgen]
+pub fn add_ring(instance_id: u32, text: &str, radius: f64, speed: f64) -> u32 {
+    if !speed.is_finite() {
+        log::warn!("add_ring: ignoring non-finite speed {}", speed);
+        return u32::MAX;
+    }
+    let speed = speed.clamp(-MAX_ROTATION_SPEED, MAX_ROTATION_SPEED);
+    let radius = if radius.is_finite() {
+        radius.clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS)
+    } else {
+        ORBIT_RADIUS
+    };
+    with_text_sphere(instance_id, |ts| {
+        let index = ts.add_ring(text, radius, speed);
+        ts.rings[index].id
+    })
+    .unwrap_or(u32::MAX)
+}
+
+/// Show `text` word-wrapped over as many rings as it needs, each holding
+/// at most `max_chars` characters. Replaces any rings added before.
+#[wasm_bindgen]
+pub fn wrap_and_display(instance_id: u32, text: &str, max_chars: u32) {
+    if with_text_sphere(instance_id, |ts| {
+        ts.wrap_and_display(text, max_chars as usize)
+    })
+    .is_none()
+    {
+        log::warn!("wrap_and_display called before the sphere was initialized");
+    }
+}
+
+/// Remove a ring by the id `add_ring` returned (the primary ring 0 cannot
+/// be removed)
+#[wasm_bindgen]
+pub fn remove_ring(instance_id: u32, ring_id: u32) {
+    let removed = with_text_sphere(instance_id, |ts| {
+        ts.ring_index(ring_id)
+            .is_some_and(|index| ts.remove_ring(index))
+    });
+    if removed != Some(true) {
+        log::warn!("remove_ring: no removable ring with id {}", ring_id);
+    }
+}
+
+/// Set how many radians the sphere rotates per pixel of mouse drag
+#[wasm_bindgen]
+pub fn set_drag_sensitivity(instance_id: u32, radians_per_pixel: f64) {
+    with_text_sphere(instance_id, |ts| ts.drag_sensitivity = radians_per_pixel);
+}
+
+/// Current `[screen_x, screen_y, z, opacity]` of a primary-ring character,
+/// or `null` if `index` is out of range
+#[wasm_bindgen]
+pub fn get_char_position(instance_id: u32, index: u32) -> JsValue {
+    with_text_sphere(instance_id, |ts| {
+        let index = index as usize;
+        if index >= ts.rings[0].characters.len() {
+            return JsValue::NULL;
+        }
+        let data = ts.project_character(0, index);
+        js_sys::Float64Array::from(&[data.screen_x, data.screen_y, data.z, data.opacity][..]).into()
+    })
+    .unwrap_or(JsValue::NULL)
+}
+
+/// Viewport rect of the sphere's container as `[x, y, width, height, top,
+/// left]`, for positioning overlays. All zeros if it is not in the page.
+#[wasm_bindgen]
+pub fn get_container_rect(instance_id: u32) -> js_sys::Float64Array {
+    let rect = with_text_sphere(instance_id, |ts| ts.container_rect()).unwrap_or([0.0; 6]);
+    js_sys::Float64Array::from(&rect[..])
+}
+
+/// Viewport rect of the sphere circle, in the same layout as
+/// `get_container_rect`
+#[wasm_bindgen]
+pub fn get_sphere_screen_rect(instance_id: u32) -> js_sys::Float64Array {
+    let rect = with_text_sphere(instance_id, |ts| ts.sphere_screen_rect()).unwrap_or([0.0; 6]);
+    js_sys::Float64Array::from(&rect[..])
+}
+
+/// Call `callback(index, char, is_entering)` whenever the pointer enters
+/// or leaves a primary-ring character (SVG backend only). Pass `null` to
+/// stop.
+#[wasm_bindgen]
+pub fn set_char_hover_callback(instance_id: u32, callback: Option<js_sys::Function>) {
+    with_text_sphere(instance_id, |ts| {
+        ts.char_hover_callback = callback;
+        ts.register_char_hover();
+    });
+}
+
+/// Call `callback(index, char, x, y)` when a primary-ring character is
+/// clicked, with (x, y) the click position within the SVG. Pass `null` to
+/// stop.
+#[wasm_bindgen]
+pub fn set_click_callback(instance_id: u32, callback: Option<js_sys::Function>) {
+    with_text_sphere(instance_id, |ts| ts.click_callback = callback);
+}
+
+/// Let clicks pass through the sphere to the page beneath it. This also
+/// turns off dragging, hover and click callbacks.
+#[wasm_bindgen]
+pub fn set_click_through(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| {
+        ts.click_through = enabled;
+        set_pointer_events(ts.renderer.surface(), !enabled);
+    });
+}
+
+/// Number of characters in the primary ring
+#[wasm_bindgen]
+pub fn get_char_count(instance_id: u32) -> u32 {
+    with_text_sphere(instance_id, |ts| ts.rings[0].characters.len() as u32).unwrap_or(0)
+}
+
+/// Index of the primary-ring character under SVG coordinates (x, y), or -1.
+/// Overlapping characters resolve to the one nearest the viewer.
+#[wasm_bindgen]
+pub fn get_character_at_screen_pos(instance_id: u32, x: f64, y: f64) -> i32 {
+    with_text_sphere(instance_id, |ts| ts.character_at(x, y))
+        .flatten()
+        .map_or(-1, |index| index as i32)
+}
+
+/// Stretch the primary ring into an ellipse (`rx == rz` gives a circle).
+/// Both radii are clamped to 10–1000.
+#[wasm_bindgen]
+pub fn set_orbit_radii(instance_id: u32, rx: f64, rz: f64) {
+    if !rx.is_finite() || !rz.is_finite() {
+        log::warn!("set_orbit_radii: ignoring non-finite radii {}, {}", rx, rz);
+        return;
+    }
+    let rx = rx.clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS);
+    let rz = rz.clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS);
+    with_text_sphere_saved(instance_id, |ts| ts.rings[0].set_radii(rx, rz));
+}
+
+/// Trace a Lissajous figure on the primary ring (a = 1, b = 2 is a figure-eight)
+#[wasm_bindgen]
+pub fn set_orbit_shape_lissajous(instance_id: u32, a: f64, b: f64, delta: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.rings[0].shape = OrbitShape::Lissajous(LissajousParams { a, b, delta });
+    });
+}
+
+/// Return the primary ring to a circular orbit of `radius` pixels
+/// (clamped to 10–1000)
+#[wasm_bindgen]
+pub fn set_orbit_shape_circle(instance_id: u32, radius: f64) {
+    if !radius.is_finite() {
+        log::warn!(
+            "set_orbit_shape_circle: ignoring non-finite radius {}",
+            radius
+        );
+        return;
+    }
+    let radius = radius.clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS);
+    with_text_sphere_saved(instance_id, |ts| {
+        let ring = &mut ts.rings[0];
+        ring.shape = OrbitShape::Circle;
+        ring.set_radii(radius, radius);
+    });
+}
+
+/// Smoothly grow or shrink the primary ring to `radius` pixels
+#[wasm_bindgen]
+pub fn set_orbit_radius(instance_id: u32, radius: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.rings[0].set_radius_target(radius.clamp(MIN_ORBIT_RADIUS, MAX_ORBIT_RADIUS));
+    });
+}
+
+/// Set how fast the primary ring's radius animates, in pixels per second
+#[wasm_bindgen]
+pub fn set_orbit_radius_speed(instance_id: u32, pixels_per_second: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.rings[0].radius_speed = pixels_per_second.abs()
+    });
+}
+
+/// Lay the primary ring's text along a helix `h` pixels tall (0 = flat ring)
+#[wasm_bindgen]
+pub fn set_helix_height(instance_id: u32, h: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.rings[0].helix_height = h);
+}
+
+/// Scale the gap between characters: below 1.0 they bunch together, above
+/// 1.0 they spread out and may overlap on the far side of the orbit.
+/// Clamped to 0–10.
+#[wasm_bindgen]
+pub fn set_letter_spacing(instance_id: u32, factor: f64) {
+    if !factor.is_finite() {
+        log::warn!("set_letter_spacing: ignoring non-finite factor {}", factor);
+        return;
+    }
+    with_text_sphere_saved(instance_id, |ts| ts.rings[0].set_letter_spacing(factor));
+}
+
+/// Center the text of every ring on `degrees` along the orbit at time zero
+/// (default 90 = front of the sphere, 0 = right). Characters move in place.
+#[wasm_bindgen]
+pub fn set_start_angle_offset(instance_id: u32, degrees: f64) {
+    if !degrees.is_finite() {
+        log::warn!(
+            "set_start_angle_offset: ignoring non-finite angle {}",
+            degrees
+        );
+        return;
+    }
+    with_text_sphere_saved(instance_id, |ts| {
+        for ring in &mut ts.rings {
+            ring.set_start_angle(degrees.to_radians());
+        }
+    });
+}
+
+/// Like `set_start_angle_offset` for the ring with id `ring_id` (0 = primary),
+/// e.g. to interlace rings
+#[wasm_bindgen]
+pub fn set_ring_start_angle_offset(instance_id: u32, ring_id: u32, degrees: f64) {
+    if !degrees.is_finite() {
+        log::warn!(
+            "set_ring_start_angle_offset: ignoring non-finite angle {}",
+            degrees
+        );
+        return;
+    }
+    let found = with_text_sphere_saved(instance_id, |ts| {
+        ts.ring_index(ring_id)
+            .map(|index| &mut ts.rings[index])
+            .map(|ring| ring.set_start_angle(degrees.to_radians()))
+    });
+    if found.flatten().is_none() {
+        log::warn!("set_ring_start_angle_offset: no ring with id {}", ring_id);
+    }
+}
+
+/// Smoothly tilt one ring towards `degrees` at its tilt speed
+#[wasm_bindgen]
+pub fn set_ring_tilt(instance_id: u32, ring_id: u32, degrees: f64) {
+    let target = inclination_from_degrees(degrees);
+    let found = with_text_sphere_saved(instance_id, |ts| {
+        ts.ring_index(ring_id)
+            .map(|index| &mut ts.rings[index])
+            .map(|ring| ring.tilt_target = target)
+    });
+    if found.flatten().is_none() {
+        log::warn!("set_ring_tilt: no ring with id {}", ring_id);
+    }
+}
+
+/// Set how fast (rad/s) a ring moves towards its tilt target
+#[wasm_bindgen]
+pub fn set_ring_tilt_speed(instance_id: u32, ring_id: u32, speed: f64) {
+    let found = with_text_sphere_saved(instance_id, |ts| {
+        ts.ring_index(ring_id)
+            .map(|index| &mut ts.rings[index])
+            .map(|ring| ring.tilt_speed = speed.abs())
+    });
+    if found.flatten().is_none() {
+        log::warn!("set_ring_tilt_speed: no ring with id {}", ring_id);
+    }
+}
+
+/// Cycle character colors around the spectrum (0 = static colors)
+#[wasm_bindgen]
+pub fn set_hue_cycle_speed(instance_id: u32, degrees_per_second: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.hue_cycle_speed = degrees_per_second;
+        ts.colors_dirty = true;
+    });
+}
+
+/// Blend character colors towards warm (front) / cool (back) hues by depth
+/// (0.0 = static colors, 1.0 = full depth coloring)
+#[wasm_bindgen]
+pub fn set_depth_color_blend(instance_id: u32, amount: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.depth_color_blend = amount.clamp(0.0, 1.0);
+        ts.colors_dirty = true;
+    });
+}
+
+/// Make characters glow, brightest at the front of the orbit
+#[wasm_bindgen]
+pub fn set_glow_enabled(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| ts.set_glow_enabled(enabled));
+}
+
+/// Set the glow blur radius (px) of the front-most character
+#[wasm_bindgen]
+pub fn set_max_glow(instance_id: u32, pixels: f64) {
+    with_text_sphere(instance_id, |ts| ts.max_glow = pixels.max(0.0));
+}
+
+/// Darken characters that pass behind the sphere
+#[wasm_bindgen]
+pub fn set_sphere_shadow(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.sphere_shadow = enabled;
+        ts.colors_dirty = true;
+    });
+}
+
+/// Set the width (px) of the soft edge of the sphere's shadow
+#[wasm_bindgen]
+pub fn set_shadow_softness(instance_id: u32, radius: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.shadow_softness = radius.max(0.0));
+}
+
+/// Smoothly grow or shrink the central sphere to `radius` pixels
+/// (clamped to 0–1000)
+#[wasm_bindgen]
+pub fn set_sphere_radius(instance_id: u32, radius: f64) {
+    if !radius.is_finite() {
+        log::warn!("set_sphere_radius: ignoring non-finite radius {}", radius);
+        return;
+    }
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.set_sphere_radius_target(radius.clamp(0.0, MAX_ORBIT_RADIUS))
+    });
+}
+
+/// Smoothly change the base letter size to `size_px` (clamped to 4–500).
+/// Characters are drawn larger in front and smaller behind by perspective.
+#[wasm_bindgen]
+pub fn set_letter_size(instance_id: u32, size_px: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.set_letter_size(size_px));
+}
+
+/// Set how fast the letter size animates, in pixels per second
+#[wasm_bindgen]
+pub fn set_letter_size_speed(instance_id: u32, pixels_per_second: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.letter_size_speed = pixels_per_second.abs()
+    });
+}
+
+/// Set how fast the sphere radius animates, in pixels per second
+#[wasm_bindgen]
+pub fn set_sphere_radius_speed(instance_id: u32, pixels_per_second: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.sphere_radius_speed = pixels_per_second.abs()
+    });
+}
+
+/// Recolor the sphere from a base `#rrggbb` color; the gradient's highlight
+/// and shadow are lighter and darker versions of it
+#[wasm_bindgen]
+pub fn set_sphere_color(instance_id: u32, hex: &str) -> Result<(), JsValue> {
+    let (h, s, v) = hex_to_hsv(hex).ok_or_else(|| JsValue::from_str("invalid sphere color"))?;
+    with_text_sphere(instance_id, |ts| {
+        ts.sphere_gradient = [
+            hsv_to_hex(h, s * 0.8, v * 1.4),
+            hsv_to_hex(h, s, v),
+            hsv_to_hex(h, s, v * 0.5),
+        ];
+        // High contrast keeps the sphere white until it is turned off
+        if ts.high_contrast.is_none() {
+            let [highlight, mid, shadow] = &ts.sphere_gradient;
+            ts.renderer.set_sphere_gradient(highlight, mid, shadow);
+        }
+    });
+    Ok(())
+}
+
+/// Validate a user-supplied hex color and return it as a CSS color
+fn parse_hex_color(hex: &str, error: &str) -> Result<String, JsValue> {
+    if hex_to_hsv(hex).is_none() {
+        return Err(JsValue::from_str(error));
+    }
+    Ok(format!("#{}", hex.trim_start_matches('#')))
+}
+
+/// Fill the background with a `#rrggbb` color; an empty string or
+/// `"transparent"` removes the background
+#[wasm_bindgen]
+pub fn set_background_color(instance_id: u32, hex: &str) -> Result<(), JsValue> {
+    let fill = background_fill(hex).map_err(|e| JsValue::from_str(&e))?;
+    with_text_sphere_saved(instance_id, |ts| ts.set_background(fill));
+    Ok(())
+}
+
+/// Write settings back into the URL hash whenever they change
+#[wasm_bindgen]
+pub fn set_sync_url_hash(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| ts.sync_url_hash = enabled);
+}
+
+/// Fill the background with a vertical gradient between two `#rrggbb` colors
+#[wasm_bindgen]
+pub fn set_background_gradient(
+    instance_id: u32,
+    hex_top: &str,
+    hex_bottom: &str,
+) -> Result<(), JsValue> {
+    let top = parse_hex_color(hex_top, "invalid background color")?;
+    let bottom = parse_hex_color(hex_bottom, "invalid background color")?;
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.renderer.set_background_gradient(&top, &bottom);
+        ts.background_color.clear();
+    });
+    Ok(())
+}
+
+/// Set how strongly letters skew when seen side-on (0 = always upright, max 1)
+#[wasm_bindgen]
+pub fn set_skew_intensity(instance_id: u32, intensity: f64) {
+    let clamped = intensity.clamp(0.0, 1.0);
+    if clamped != intensity {
+        log::warn!(
+            "skew intensity {} out of range, clamped to {}",
+            intensity,
+            clamped
+        );
+    }
+    with_text_sphere_saved(instance_id, |ts| ts.skew_intensity = clamped);
+}
+
+/// Apply several settings at once from a JSON object, e.g.
+/// `{"text": "hello", "rotation_speed": 0.5}`. Keys that are absent keep
+/// their current values; nothing is applied if any value is invalid.
+#[wasm_bindgen]
+pub fn config_from_json(instance_id: u32, json: &str) -> Result<(), JsValue> {
+    let config = parse_config(json).map_err(|e| JsValue::from_str(&e))?;
+    with_text_sphere_saved(instance_id, |ts| ts.apply_config(&config));
+    Ok(())
+}
+
+/// Current settings as JSON, suitable for passing back to `config_from_json`
+#[wasm_bindgen]
+pub fn get_config_json(instance_id: u32) -> String {
+    with_text_sphere(instance_id, |ts| {
+        serde_json::to_string(&ts.config()).expect("config serializes")
+    })
+    .unwrap_or_else(|| "{}".to_string())
+}
+
+/// Forget settings saved by previous visits (current settings are kept)
+#[wasm_bindgen]
+pub fn clear_saved_config(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| remove_from_local_storage(&ts.storage_key));
+}
+
+/// Rolling average frame rate over the last 60 frames
+#[wasm_bindgen]
+pub fn get_fps(instance_id: u32) -> f64 {
+    with_text_sphere(instance_id, |ts| ts.fps()).unwrap_or(0.0)
+}
+
+/// Duration of the most recent frame in milliseconds
+#[wasm_bindgen]
+pub fn get_frame_time_ms(instance_id: u32) -> f64 {
+    with_text_sphere(instance_id, |ts| ts.last_frame_time() * 1000.0).unwrap_or(0.0)
+}
+
+/// Show or hide an on-screen FPS counter
+#[wasm_bindgen]
+pub fn set_show_fps(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.show_fps = enabled;
+        if !enabled {
+            ts.renderer.set_status_text("");
+        }
+    });
+}
+
+/// Automatically reduce letter size and character count when FPS drops
+#[wasm_bindgen]
+pub fn set_adaptive_quality(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| ts.set_adaptive_quality(enabled));
+}
+
+/// Set the FPS below which quality drops and above which it is restored
+#[wasm_bindgen]
+pub fn set_fps_thresholds(instance_id: u32, low: f64, high: f64) {
+    if low >= high {
+        log::warn!(
+            "set_fps_thresholds: low ({}) must be below high ({})",
+            low,
+            high
+        );
+        return;
+    }
+    with_text_sphere(instance_id, |ts| {
+        ts.fps_low = low;
+        ts.fps_high = high;
+    });
+}
+
+/// Current frame as a standalone SVG document (empty if not running or
+/// drawing to a canvas)
+#[wasm_bindgen]
+pub fn export_svg(instance_id: u32) -> String {
+    with_text_sphere(instance_id, |ts| {
+        if ts.renderer.backend() != RenderBackend::Svg {
+            log::warn!("export_svg: only the SVG backend can be exported");
+            return String::new();
+        }
+        ts.renderer
+            .surface()
+            .dyn_ref::<web_sys::Element>()
+            .map(export_svg_markup)
+            .unwrap_or_default()
+    })
+    .unwrap_or_default()
+}
+
+/// Current frame as a PNG Blob, for screenshot tools. SVG frames are
+/// rasterized through an offscreen canvas the size of the SVG; canvas
+/// backends encode their canvas directly. Rejects if there is no sphere or
+/// the frame can't be encoded.
+#[wasm_bindgen]
+pub fn capture_frame(instance_id: u32) -> js_sys::Promise {
+    let source = with_text_sphere(instance_id, |ts| {
+        if ts.renderer.backend() == RenderBackend::Svg {
+            let svg: &web_sys::Element = ts.renderer.surface().dyn_ref()?;
+            let size = |name| {
+                svg.get_attribute(name)
+                    .and_then(|value| value.parse::<f64>().ok())
+                    .map_or(0, |value| value.round() as u32)
+            };
+            return Some(FrameSource::Svg {
+                markup: export_svg_markup(svg),
+                width: size("width"),
+                height: size("height"),
+            });
+        }
+        // The WebGL drawing buffer may have been cleared since the last
+        // frame; redraw so toBlob's synchronous copy sees the frame
+        if ts.renderer.backend() == RenderBackend::WebGl {
+            ts.animate(0.0);
+        }
+        ts.renderer
+            .surface()
+            .dyn_ref()
+            .cloned()
+            .map(FrameSource::Canvas)
+    })
+    .flatten();
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let blob = match source {
+            Some(FrameSource::Svg {
+                markup,
+                width,
+                height,
+            }) => svg_to_png(&markup, width, height).await?,
+            Some(FrameSource::Canvas(canvas)) => canvas_to_png(&canvas).await?,
+            None => return Err(JsValue::from_str("no sphere to capture")),
+        };
+        Ok(blob.into())
+    })
+}
+
+/// Save the current frame as an SVG file via a browser download
+#[wasm_bindgen]
+pub fn download_svg(instance_id: u32, filename: &str) {
+    let markup = export_svg(instance_id);
+    if markup.is_empty() {
+        log::warn!("download_svg: nothing to export");
+        return;
+    }
+    if let Err(err) = download_text(filename, &markup, "image/svg+xml") {
+        log::error!("download_svg failed: {:?}", err);
+    }
+}
+
+/// Jump to a rotation angle (radians, normalized to [0, 2π)) and redraw
+#[wasm_bindgen]
+pub fn seek(instance_id: u32, angle_radians: f64) {
+    with_text_sphere(instance_id, |ts| ts.seek(angle_radians));
+}
+
+/// Set the camera distance used for perspective (min 1.0).
+/// Below ~50 gives a fisheye look; above ~2000 is nearly orthographic.
+#[wasm_bindgen]
+pub fn set_perspective_distance(instance_id: u32, d: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.perspective_distance = d.max(1.0));
+}
+
+/// Switch between perspective and orthographic projection
+#[wasm_bindgen]
+pub fn set_orthographic(instance_id: u32, enabled: bool) {
+    with_text_sphere_saved(instance_id, |ts| ts.use_ortho = enabled);
+}
+
+/// Fade the text in one character at a time; fires `reveal_complete` on the
+/// SVG (when enabled) once the last character is fully visible
+#[wasm_bindgen]
+pub fn start_reveal(instance_id: u32, char_delay_ms: f64, fade_duration_ms: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.start_reveal(char_delay_ms / 1000.0, fade_duration_ms / 1000.0)
+    });
+}
+
+/// Choose the reveal order: 0 = sequential, 1 = reverse, 2 = random, 3 = center-out
+#[wasm_bindgen]
+pub fn set_reveal_order(instance_id: u32, order: u8) {
+    match RevealOrder::from_u8(order) {
+        Some(order) => {
+            with_text_sphere_saved(instance_id, |ts| ts.reveal_order = order);
+        }
+        None => log::warn!("set_reveal_order: unknown order {}", order),
+    }
+}
+
+/// Reseed the random number generator used by randomized effects. The seed
+/// is a u32 so JS can pass a plain number rather than a BigInt.
+#[wasm_bindgen]
+pub fn set_random_seed(instance_id: u32, seed: u32) {
+    with_text_sphere(instance_id, |ts| ts.rng = XorShift64::new(seed.into()));
+}
+
+/// Show random characters for up to `duration_ms` before settling on the
+/// real text; fires `scramble_complete` on the SVG (when enabled) once every
+/// character settles
+#[wasm_bindgen]
+pub fn scramble_text(instance_id: u32, duration_ms: f64) {
+    with_text_sphere(instance_id, |ts| ts.start_scramble(duration_ms / 1000.0));
+}
+
+/// Scatter the characters to random points on the orbit and spring them
+/// back into place over about `duration_ms`; rotation carries on meanwhile
+#[wasm_bindgen]
+pub fn randomize_positions(instance_id: u32, duration_ms: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.randomize_positions(duration_ms / 1000.0, None)
+    });
+}
+
+/// Like `randomize_positions`, with the same scatter every time for `seed`
+#[wasm_bindgen]
+pub fn randomize_positions_with_seed(instance_id: u32, seed: u32, duration_ms: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.randomize_positions(duration_ms / 1000.0, Some(seed.into()))
+    });
+}
+
+/// Make letter size oscillate; amplitude is a fraction of the size (0 = off)
+#[wasm_bindgen]
+pub fn set_pulse(instance_id: u32, amplitude: f64, frequency_hz: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.pulse_amplitude = amplitude.clamp(0.0, 1.0);
+        ts.pulse_frequency = frequency_hz;
+        ts.beat_pulse = false;
+    });
+}
+
+impl TextSphere {
+    /// Pulse on every beat at `bpm`, starting the pulse if it is off
+    fn set_beat_pulse(&mut self, bpm: f64) {
+        self.pulse_frequency = bpm / 60.0;
+        self.beat_pulse = true;
+        if self.pulse_amplitude == 0.0 {
+            self.pulse_amplitude = BEAT_PULSE_AMPLITUDE;
+        }
+    }
+
+    /// Record a tap at `now` (ms) and, once there are two or more, set the
+    /// beat pulse to the average tempo of the last TAP_TEMPO_TAPS taps
+    fn tap_tempo(&mut self, now: f64) {
+        if self
+            .tap_times
+            .back()
+            .is_some_and(|&last| now - last > TAP_RESET_MS)
+        {
+            self.tap_times.clear();
+        }
+        if self.tap_times.len() == TAP_TEMPO_TAPS {
+            self.tap_times.pop_front();
+        }
+        self.tap_times.push_back(now);
+
+        let (Some(&first), Some(&last)) = (self.tap_times.front(), self.tap_times.back()) else {
+            return;
+        };
+        let intervals = self.tap_times.len() - 1;
+        if intervals == 0 || last <= first {
+            return;
+        }
+        let avg_interval_ms = (last - first) / intervals as f64;
+        self.detected_bpm = 60000.0 / avg_interval_ms;
+        self.set_beat_pulse(self.detected_bpm);
+        self.beat_origin = self.elapsed;
+    }
+}
+
+/// Set the fraction of its speed the orbit keeps while the window is
+/// blurred (1 = no slowdown, 0 = stop)
+#[wasm_bindgen]
+pub fn set_blur_speed_factor(instance_id: u32, factor: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.blur_speed_factor = factor.clamp(0.0, 1.0)
+    });
+}
+
+/// Pulse letter size on every beat at `bpm`: a sharp growth on the beat
+/// that decays back to normal size before the next one
+#[wasm_bindgen]
+pub fn set_beat_pulse(instance_id: u32, bpm: f64) {
+    if !bpm.is_finite() || bpm <= 0.0 {
+        log::warn!("set_beat_pulse: bpm must be a positive number, got {}", bpm);
+        return;
+    }
+    with_text_sphere(instance_id, |ts| ts.set_beat_pulse(bpm));
+}
+
+/// Call on each beat to set the beat pulse tempo from the average interval
+/// between the last 8 calls. A pause of over 2 seconds starts a new count.
+#[wasm_bindgen]
+pub fn tap_tempo(instance_id: u32) {
+    let Some(now) = web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+    else {
+        return;
+    };
+    with_text_sphere(instance_id, |ts| ts.tap_tempo(now));
+}
+
+/// Tempo (BPM) last measured by `tap_tempo`, or 0 before two taps
+#[wasm_bindgen]
+pub fn get_detected_bpm(instance_id: u32) -> f64 {
+    with_text_sphere(instance_id, |ts| ts.detected_bpm).unwrap_or(0.0)
+}
+
+/// Make characters bounce `frequency` times per orbit (height 0 = off)
+#[wasm_bindgen]
+pub fn set_bounce(instance_id: u32, height_px: f64, frequency: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.bounce_height = height_px.max(0.0);
+        ts.bounce_frequency = frequency;
+    });
+}
+
+/// Point the light along `(x, y, z)` (x right, y up, z towards the viewer);
+/// the vector is normalized. Default `(0, 1, 0.5)`: above and in front.
+#[wasm_bindgen]
+pub fn set_light_direction(instance_id: u32, x: f64, y: f64, z: f64) -> Result<(), JsValue> {
+    let direction =
+        normalize(x, y, z).ok_or_else(|| JsValue::from_str("light direction must not be zero"))?;
+    with_text_sphere(instance_id, |ts| {
+        ts.light_direction = direction;
+        ts.light_orbiting = false;
+        ts.colors_dirty = true;
+    });
+    Ok(())
+}
+
+/// Send the light circling the sphere at `radians_per_second`, sweeping a
+/// highlight around it; 0 freezes the light where it is. A light passing
+/// behind the sphere rim-lights the characters facing away from it.
+#[wasm_bindgen]
+pub fn set_light_speed(instance_id: u32, radians_per_second: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.light_speed = radians_per_second;
+        ts.light_orbiting = true;
+        ts.update_light(0.0);
+        ts.colors_dirty = true;
+    });
+}
+
+/// Distance (px) of the orbiting light from the sphere's vertical axis: a
+/// wide orbit lights the characters from the side, a tight one from above
+#[wasm_bindgen]
+pub fn set_light_orbit_radius(instance_id: u32, r: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.light_orbit_radius = r.max(0.0);
+        ts.update_light(0.0);
+        ts.colors_dirty = true;
+    });
+}
+
+/// Color the direct and rim light with a `#rrggbb` color (default white)
+#[wasm_bindgen]
+pub fn set_light_color(instance_id: u32, hex: &str) -> Result<(), JsValue> {
+    let color = hex_to_rgb(hex).ok_or_else(|| JsValue::from_str("invalid light color"))?;
+    with_text_sphere(instance_id, |ts| {
+        ts.light_color = color;
+        ts.colors_dirty = true;
+    });
+    Ok(())
+}
+
+/// Balance the lighting: each color is scaled by `ambient + diffuse * cos`
+/// of the angle between the character's normal and the light (both 0–1).
+/// The default ambient 1, diffuse 0 turns lighting off.
+#[wasm_bindgen]
+pub fn set_lighting_coefficients(instance_id: u32, ambient: f64, diffuse: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.light_ambient = ambient.clamp(0.0, 1.0);
+        ts.light_diffuse = diffuse.clamp(0.0, 1.0);
+        ts.colors_dirty = true;
+    });
+}
+
+/// Rock the orbit plane back and forth by `amplitude_deg` either side of
+/// its inclination, `frequency_hz` times a second, like a spinning coin
+/// settling on a table (amplitude 0 = off)
+#[wasm_bindgen]
+pub fn set_orbit_plane_wobble(instance_id: u32, amplitude_deg: f64, frequency_hz: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.wobble_amplitude = amplitude_deg.to_radians();
+        ts.wobble_frequency = frequency_hz;
+    });
+}
+
+/// Set how characters near the cursor react (0 = off, 1 = attract, 2 = repel)
+#[wasm_bindgen]
+pub fn set_cursor_mode(instance_id: u32, mode: u8) {
+    match CursorMode::from_u8(mode) {
+        Some(mode) => {
+            with_text_sphere_saved(instance_id, |ts| ts.cursor_mode = mode);
+        }
+        None => log::warn!("set_cursor_mode: unknown mode {}", mode),
+    }
+}
+
+/// Set the strength of the cursor force (force = strength / distance²)
+#[wasm_bindgen]
+pub fn set_cursor_strength(instance_id: u32, strength: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.cursor_strength = strength.max(0.0));
+}
+
+/// Pull characters near the cursor towards it. Kept from before repel mode:
+/// `true` is `set_cursor_mode(1)`, and `false` turns attraction (but not
+/// repulsion) off.
+#[wasm_bindgen]
+pub fn set_cursor_attract(instance_id: u32, enabled: bool) {
+    let attracting =
+        with_text_sphere(instance_id, |ts| ts.cursor_mode == CursorMode::Attract).unwrap_or(false);
+    if enabled {
+        set_cursor_mode(instance_id, CursorMode::Attract as u8);
+    } else if attracting {
+        set_cursor_mode(instance_id, CursorMode::None as u8);
+    }
+}
+
+/// Same as `set_cursor_strength`, kept from before repel mode
+#[wasm_bindgen]
+pub fn set_attract_strength(instance_id: u32, strength: f64) {
+    set_cursor_strength(instance_id, strength);
+}
+
+/// Set the furthest (px) the cursor can move a character
+#[wasm_bindgen]
+pub fn set_max_displacement(instance_id: u32, max_displacement_px: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.max_displacement = max_displacement_px.max(0.0)
+    });
+}
+
+/// Set how displaced characters spring back to their orbit. Damping below
+/// 2√k overshoots and oscillates; above it they ease back smoothly
+#[wasm_bindgen]
+pub fn set_spring_params(instance_id: u32, k: f64, damping: f64) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.spring_stiffness = k.max(0.0);
+        ts.spring_damping = damping.max(0.0);
+    });
+}
+
+/// Set the distance (px) beyond which the cursor has no effect
+#[wasm_bindgen]
+pub fn set_cursor_influence_radius(instance_id: u32, radius: f64) {
+    with_text_sphere_saved(instance_id, |ts| ts.influence_radius = radius.max(0.0));
+}
+
+/// Set the font for all characters, e.g. `"Times New Roman", serif` or a
+/// loaded web font. Names containing spaces are quoted automatically.
+#[wasm_bindgen]
+pub fn set_font_family(instance_id: u32, family: &str) {
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.font_family = css_font_family(family);
+        ts.apply_text_style();
+    });
+}
+
+/// Set the font weight for all characters: `normal`, `bold`, `bolder`,
+/// `lighter`, or `100`–`900` in steps of 100
+#[wasm_bindgen]
+pub fn set_font_weight(instance_id: u32, weight: &str) -> Result<(), JsValue> {
+    if !is_valid_font_weight(weight) {
+        return Err(JsValue::from_str("invalid font weight"));
+    }
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.font_weight = weight.to_string();
+        ts.apply_text_style();
+    });
+    Ok(())
+}
+
+/// Turn an event on or off. Events are fired on the SVG element as
+/// `CustomEvent`s: `reveal_complete`, `rotation_lap` (detail `{lap_count,
+/// elapsed_ms}`), `scramble_complete` and `warp_complete`. All are off by default.
+#[wasm_bindgen]
+pub fn set_event_enabled(instance_id: u32, event_name: &str, enabled: bool) -> Result<(), JsValue> {
+    let Some(&name) = EVENT_NAMES.iter().find(|&&name| name == event_name) else {
+        return Err(JsValue::from_str(&format!("unknown event: {}", event_name)));
+    };
+    with_text_sphere(instance_id, |ts| {
+        ts.enabled_events
+            .retain(|&enabled_name| enabled_name != name);
+        if enabled {
+            ts.enabled_events.push(name);
+        }
+    });
+    Ok(())
+}
+
+/// Highlight a primary-ring character: it grows and takes the highlight color
+#[wasm_bindgen]
+pub fn set_char_highlight(instance_id: u32, index: u32, enabled: bool) {
+    let found = with_text_sphere(instance_id, |ts| {
+        let index = index as usize;
+        let Some(character) = ts.rings[0].characters.get_mut(index) else {
+            return false;
+        };
+        let newly_highlighted = enabled && !character.highlighted;
+        character.highlighted = enabled;
+        if newly_highlighted {
+            ts.play_char_sound(index, None, CHAR_SOUND_DURATION_MS);
+        }
+        true
+    });
+    if found == Some(false) {
+        log::warn!("set_char_highlight: no character at index {}", index);
+    }
+}
+
+/// Play a `duration_ms` tone of `frequency_hz` for a primary-ring
+/// character; a frequency of 0 uses the character's entry in the sound map.
+/// Browsers only allow sound once the sphere has been clicked.
+#[wasm_bindgen]
+pub fn play_char_sound(instance_id: u32, index: u32, frequency_hz: f64, duration_ms: f64) {
+    let found = with_text_sphere(instance_id, |ts| {
+        let index = index as usize;
+        if index >= ts.rings[0].characters.len() {
+            return false;
+        }
+        if ts.audio_context.is_none() {
+            log::warn!("play_char_sound: audio starts after the first click on the sphere");
+        }
+        let freq = (frequency_hz > 0.0).then_some(frequency_hz);
+        ts.play_char_sound(index, freq, duration_ms);
+        true
+    });
+    if found == Some(false) {
+        log::warn!("play_char_sound: no character at index {}", index);
+    }
+}
+
+/// Give each primary-ring character a tone frequency (Hz) by index, played
+/// when it is clicked or highlighted. Characters past the end stay silent.
+#[wasm_bindgen]
+pub fn set_char_sound_map(instance_id: u32, frequencies: js_sys::Float64Array) {
+    with_text_sphere(instance_id, |ts| ts.char_sound_map = frequencies.to_vec());
+}
+
+/// Speed the rotation up with microphone loudness (quiet = slow, loud =
+/// fast). Resolves once the microphone is live; rejects if access is denied
+/// or the browser has no `getUserMedia`.
+#[wasm_bindgen]
+pub fn enable_mic_input(instance_id: u32) -> js_sys::Promise {
+    let text_sphere = text_sphere_handle(instance_id);
+    wasm_bindgen_futures::future_to_promise(async move {
+        let text_sphere =
+            text_sphere.ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+        open_mic_input(text_sphere).await?;
+        Ok(JsValue::UNDEFINED)
+    })
+}
+
+/// Size characters like an equalizer: the spectrum is split into `bands`
+/// equal frequency bands and each character grows with the level of the
+/// band nearest its position in the ring. 0 turns it off. Needs
+/// `enable_mic_input`.
+#[wasm_bindgen]
+pub fn set_frequency_mode(instance_id: u32, bands: u32) {
+    with_text_sphere(instance_id, |ts| {
+        if bands > 0 && ts.mic_input.is_none() {
+            log::warn!("set_frequency_mode: no microphone input; call enable_mic_input");
+        }
+        ts.frequency_bands = bands as usize;
+    });
+}
+
+/// Set how much a character grows at its band's full level (2 = three times
+/// the letter size)
+#[wasm_bindgen]
+pub fn set_frequency_amplitude_factor(instance_id: u32, factor: f64) {
+    with_text_sphere(instance_id, |ts| ts.band_amplitude_factor = factor.max(0.0));
+}
+
+/// Stop listening to the microphone and go back to the normal speed
+#[wasm_bindgen]
+pub fn disable_mic_input(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| ts.close_mic_input());
+}
+
+/// Turn character sounds on or off
+#[wasm_bindgen]
+pub fn set_audio_enabled(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| {
+        ts.audio_enabled = enabled;
+        if let Some(context) = &ts.audio_context {
+            let _ = if enabled {
+                context.resume()
+            } else {
+                context.suspend()
+            };
+        }
+    });
+}
+
+/// Remove every character highlight
+#[wasm_bindgen]
+pub fn clear_all_highlights(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| {
+        for character in ts.rings.iter_mut().flat_map(|ring| &mut ring.characters) {
+            character.highlighted = false;
+        }
+    });
+}
+
+/// Set the fill of highlighted characters (`#rrggbb`)
+#[wasm_bindgen]
+pub fn set_highlight_color(instance_id: u32, hex: &str) -> Result<(), JsValue> {
+    let color = parse_hex_color(hex, "invalid highlight color")?;
+    with_text_sphere(instance_id, |ts| ts.highlight_color = color);
+    Ok(())
+}
+
+/// Outline every character with a `width` px stroke of `color` (`#rrggbb`);
+/// a width of 0 removes the outline
+#[wasm_bindgen]
+pub fn set_text_stroke(instance_id: u32, width: f64, color: &str) -> Result<(), JsValue> {
+    let color = parse_hex_color(color, "invalid stroke color")?;
+    with_text_sphere(instance_id, |ts| {
+        ts.stroke_width = width.max(0.0);
+        ts.stroke_color = color;
+        ts.apply_text_style();
+    });
+    Ok(())
+}
+
+/// Give every character a drop shadow offset by `(offset_x, offset_y)` px
+/// with a `blur` px radius. `color` is `#rrggbb`, `rgb(...)` or
+/// `rgba(r,g,b,a)` for a translucent shadow. All zeros removes the shadow.
+/// Not drawn by the WebGL backend.
+#[wasm_bindgen]
+pub fn set_text_shadow(
+    instance_id: u32,
+    offset_x: f64,
+    offset_y: f64,
+    blur: f64,
+    color: &str,
+) -> Result<(), JsValue> {
+    let shadow = if offset_x == 0.0 && offset_y == 0.0 && blur == 0.0 {
+        None
     } else {
-        log::error!("Failed to initialize TextSphere");
+        let color = css_shadow_color(color)
+            .ok_or_else(|| JsValue::from_str(&format!("invalid shadow color: {}", color)))?;
+        Some(TextShadow {
+            dx: offset_x,
+            dy: offset_y,
+            blur: blur.max(0.0),
+            color,
+        })
+    };
+    with_text_sphere(instance_id, |ts| {
+        ts.text_shadow = shadow;
+        ts.apply_text_style();
+    });
+    Ok(())
+}
+
+/// Scale each character's shadow offset by its perspective, so characters
+/// at the back of the sphere cast a less prominent shadow (default off)
+#[wasm_bindgen]
+pub fn set_text_shadow_perspective(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| {
+        ts.shadow_perspective = enabled;
+        ts.apply_text_style();
+    });
+}
+
+/// Set the opacity of the character outline (0 = invisible, 1 = opaque)
+#[wasm_bindgen]
+pub fn set_stroke_opacity(instance_id: u32, alpha: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.stroke_opacity = alpha.clamp(0.0, 1.0);
+        ts.apply_text_style();
+    });
+}
+
+/// Leave `length` fading ghost copies behind each character; each is
+/// `decay` times as opaque as the one before. `set_trail(0, 0.0)` turns it off.
+#[wasm_bindgen]
+pub fn set_trail(instance_id: u32, length: usize, decay: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.set_trail(length, decay.clamp(0.0, 1.0))
+    });
+}
+
+/// Warp burst over `duration_ms`: the rings spin up, the characters fly off
+/// screen, then fade back in at the original speed. Fires `warp_complete`
+/// on the SVG (when enabled) at the end.
+#[wasm_bindgen]
+pub fn trigger_warp(instance_id: u32, duration_ms: f64) {
+    with_text_sphere(instance_id, |ts| ts.start_warp(duration_ms / 1000.0));
+}
+
+/// Smoothly switch the primary ring's direction: clockwise (viewed from
+/// above) or anticlockwise. It slows, stops and spins back up.
+#[wasm_bindgen]
+pub fn set_orbit_direction(instance_id: u32, clockwise: bool) {
+    with_text_sphere_saved(instance_id, |ts| ts.set_orbit_direction(clockwise));
+}
+
+/// Smoothly reverse the primary ring's direction of rotation
+#[wasm_bindgen]
+pub fn reverse(instance_id: u32) {
+    with_text_sphere_saved(instance_id, |ts| {
+        let clockwise = ts.rings[0].speed_target < 0.0;
+        ts.set_orbit_direction(clockwise);
+    });
+}
+
+/// Ease the primary ring from its current speed to `target_speed` rad/s
+/// over `duration_ms`, along the curve chosen with `set_spin_easing`. The
+/// speed then stays at the target. Preferred over calling
+/// `set_rotation_speed` every frame from JavaScript.
+#[wasm_bindgen]
+pub fn spin_up(instance_id: u32, target_speed: f64, duration_ms: f64) {
+    if !target_speed.is_finite() {
+        log::warn!("spin_up: ignoring non-finite speed {}", target_speed);
+        return;
+    }
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.spin_up(target_speed, duration_ms / 1000.0)
+    });
+}
+
+/// Ease the primary ring to a stop over `duration_ms`
+#[wasm_bindgen]
+pub fn spin_down(instance_id: u32, duration_ms: f64) {
+    spin_up(instance_id, 0.0, duration_ms);
+}
+
+/// Select the curve used by `spin_up`: "linear", "ease-in", "ease-out"
+/// (default), "ease-in-out" or "spring"
+#[wasm_bindgen]
+pub fn set_spin_easing(instance_id: u32, name: &str) -> Result<(), JsValue> {
+    let ease = EaseMode::from_name(name)
+        .ok_or_else(|| JsValue::from_str(&format!("unknown easing: {}", name)))?;
+    with_text_sphere(instance_id, |ts| ts.spin_ease = ease);
+    Ok(())
+}
+
+/// Set how quickly the primary ring changes speed when reversing, in rad/s²
+#[wasm_bindgen]
+pub fn set_rotation_acceleration(instance_id: u32, radians_per_second_squared: f64) {
+    with_text_sphere(instance_id, |ts| {
+        ts.rings[0].speed_acceleration = radians_per_second_squared.abs()
+    });
+}
+
+/// Pin a primary-ring character at `angle` radians on the orbit while the
+/// rest keep rotating (π/2 is front and center)
+#[wasm_bindgen]
+pub fn freeze_character(instance_id: u32, index: u32, angle: f64) {
+    if !angle.is_finite() {
+        log::warn!("freeze_character: ignoring non-finite angle {}", angle);
+        return;
+    }
+    let found = with_text_sphere(instance_id, |ts| {
+        ts.rings[0]
+            .characters
+            .get_mut(index as usize)
+            .map(|character| character.frozen = Some(angle.rem_euclid(2.0 * PI)))
+            .is_some()
+    });
+    if found == Some(false) {
+        log::warn!("freeze_character: no character at index {}", index);
+    }
+}
+
+/// Let a character frozen with `freeze_character` orbit again
+#[wasm_bindgen]
+pub fn unfreeze_character(instance_id: u32, index: u32) {
+    let found = with_text_sphere(instance_id, |ts| {
+        ts.rings[0]
+            .characters
+            .get_mut(index as usize)
+            .map(|character| character.frozen = None)
+            .is_some()
+    });
+    if found == Some(false) {
+        log::warn!("unfreeze_character: no character at index {}", index);
+    }
+}
+
+/// Override the reading direction (Auto detects it from the text)
+#[wasm_bindgen]
+pub fn set_text_direction(instance_id: u32, dir: TextDirection) {
+    with_text_sphere(instance_id, |ts| {
+        ts.text_direction = dir;
+        ts.apply_text_direction();
+    });
+}
+
+/// Change the case of the displayed text (a `TextTransform` discriminant).
+/// The current text is re-transformed straight away.
+#[wasm_bindgen]
+pub fn set_text_transform(instance_id: u32, mode: u8) -> Result<(), JsValue> {
+    let transform = TextTransform::from_mode(mode)
+        .ok_or_else(|| JsValue::from_str(&format!("unknown text transform: {}", mode)))?;
+    with_text_sphere(instance_id, |ts| ts.set_text_transform(transform));
+    Ok(())
+}
+
+/// Give a primary-ring character a fixed `#rrggbb` fill
+#[wasm_bindgen]
+pub fn set_char_custom_color(instance_id: u32, index: u32, hex: &str) -> Result<(), JsValue> {
+    let color = parse_hex_color(hex, "invalid character color")?;
+    let found = with_text_sphere(instance_id, |ts| {
+        ts.colors_dirty = true;
+        ts.rings[0]
+            .characters
+            .get_mut(index as usize)
+            .map(|character| character.custom_color = Some(color))
+            .is_some()
+    });
+    if found == Some(false) {
+        log::warn!("set_char_custom_color: no character at index {}", index);
+    }
+    Ok(())
+}
+
+/// Shift one primary-ring character along the orbit by `radians` from its
+/// laid-out position (0 = back in place). Neighbours don't move, so large
+/// offsets can make characters overlap. Kept while the text length is
+/// unchanged.
+#[wasm_bindgen]
+pub fn set_char_orbit_offset(instance_id: u32, index: u32, radians: f64) {
+    if !radians.is_finite() {
+        log::warn!(
+            "set_char_orbit_offset: ignoring non-finite offset {}",
+            radians
+        );
+        return;
+    }
+    let found = with_text_sphere(instance_id, |ts| {
+        ts.rings[0]
+            .characters
+            .get_mut(index as usize)
+            .map(|character| character.orbit_offset = radians)
+            .is_some()
+    });
+    if found == Some(false) {
+        log::warn!("set_char_orbit_offset: no character at index {}", index);
+    }
+}
+
+/// Return a primary-ring character to its derived color
+#[wasm_bindgen]
+pub fn clear_char_custom_color(instance_id: u32, index: u32) {
+    let found = with_text_sphere(instance_id, |ts| {
+        ts.colors_dirty = true;
+        ts.rings[0]
+            .characters
+            .get_mut(index as usize)
+            .map(|character| character.custom_color = None)
+            .is_some()
+    });
+    if found == Some(false) {
+        log::warn!("clear_char_custom_color: no character at index {}", index);
+    }
+}
+
+/// Give every primary-ring character the same `#rrggbb` fill
+#[wasm_bindgen]
+pub fn set_all_chars_color(instance_id: u32, hex: &str) -> Result<(), JsValue> {
+    let color = parse_hex_color(hex, "invalid character color")?;
+    with_text_sphere(instance_id, |ts| {
+        ts.colors_dirty = true;
+        for character in &mut ts.rings[0].characters {
+            character.custom_color = Some(color.clone());
+        }
+    });
+    Ok(())
+}
+
+/// Effective fill of a primary-ring character (custom or derived), or an
+/// empty string if `index` is out of range
+#[wasm_bindgen]
+pub fn get_char_color(instance_id: u32, index: u32) -> String {
+    with_text_sphere(instance_id, |ts| {
+        let index = index as usize;
+        if index >= ts.rings[0].characters.len() {
+            return String::new();
+        }
+        ts.color_for(&ts.project_character(0, index))
+    })
+    .unwrap_or_default()
+}
+
+/// Select a named color scheme: "rainbow" (default), "fire", "ice",
+/// "neon", "monochrome", "pastel" or "earth"
+#[wasm_bindgen]
+pub fn set_color_scheme(instance_id: u32, name: &str) -> Result<(), JsValue> {
+    let scheme = ColorScheme::from_name(name)
+        .ok_or_else(|| JsValue::from_str(&format!("unknown color scheme: {}", name)))?;
+    with_text_sphere_saved(instance_id, |ts| {
+        ts.color_scheme = scheme;
+        ts.colors_dirty = true;
+    });
+    Ok(())
+}
+
+/// Keep the orbit text in sync with a WebSocket feed: each text message
+/// replaces the text, and dropped connections are retried with backoff
+#[wasm_bindgen]
+pub fn connect_text_feed(instance_id: u32, url: &str) -> Result<(), JsValue> {
+    let text_sphere = text_sphere_handle(instance_id)
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+    {
+        let mut ts = text_sphere.borrow_mut();
+        ts.close_text_feed();
+        ts.text_feed_url = Some(url.to_string());
+        ts.text_feed_retry_delay = FEED_RETRY_DELAY;
+    }
+    open_text_feed(&text_sphere).inspect_err(|_| text_sphere.borrow_mut().text_feed_url = None)
+}
+
+/// Close the text feed and stop reconnecting
+#[wasm_bindgen]
+pub fn disconnect_text_feed(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| ts.close_text_feed());
+}
+
+/// Keep the rotation in step with every other tab that enables sync on
+/// `channel_name`. One tab is elected leader and broadcasts its angle; the
+/// others follow it, and elect a new leader if it closes.
+#[wasm_bindgen]
+pub fn enable_sync(instance_id: u32, channel_name: &str) -> Result<(), JsValue> {
+    let text_sphere = text_sphere_handle(instance_id)
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+    open_tab_sync(&text_sphere, channel_name)
+}
+
+/// Leave the sync channel and resume rotating independently
+#[wasm_bindgen]
+pub fn disable_sync(instance_id: u32) {
+    with_text_sphere(instance_id, |ts| ts.close_tab_sync());
+}
+
+/// Replace the text with the clipboard's (trimmed, and cut to
+/// `set_max_clipboard_chars` characters). Resolves with the text that was
+/// set; rejects with a DOMException if clipboard access is denied, or if
+/// the clipboard holds no text (the text is then left unchanged).
+#[wasm_bindgen]
+pub fn set_text_from_clipboard(instance_id: u32) -> js_sys::Promise {
+    let text_sphere = text_sphere_handle(instance_id);
+    wasm_bindgen_futures::future_to_promise(async move {
+        let text_sphere =
+            text_sphere.ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+        let text = paste_clipboard_text(text_sphere).await?;
+        Ok(JsValue::from_str(&text))
+    })
+}
+
+/// Set how many characters of pasted text are kept (default 100)
+#[wasm_bindgen]
+pub fn set_max_clipboard_chars(instance_id: u32, n: u32) {
+    with_text_sphere(instance_id, |ts| ts.max_clipboard_chars = n as usize);
+}
+
+/// Let Ctrl+V (Cmd+V on macOS) replace the text with the clipboard's
+#[wasm_bindgen]
+pub fn set_clipboard_paste_enabled(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| ts.clipboard_paste_enabled = enabled);
+}
+
+/// Load the orbit text from a plain-text URL. Characters are dimmed while
+/// the request is in flight; failures fire a `text-fetch-error` event whose
+/// detail `kind` is "timeout", "cors", "network", "http" or "other".
+#[wasm_bindgen]
+pub fn fetch_text(instance_id: u32, url: &str) {
+    let Some(text_sphere) = text_sphere_handle(instance_id) else {
+        log::warn!("fetch_text called before the sphere was initialized");
+        return;
+    };
+    let generation = {
+        let mut ts = text_sphere.borrow_mut();
+        ts.text_loading = true;
+        ts.text_fetch_generation = ts.text_fetch_generation.wrapping_add(1);
+        ts.text_fetch_generation
+    };
+
+    let url = url.to_string();
+    wasm_bindgen_futures::spawn_local(async move {
+        let result = fetch_plain_text(&url).await;
+
+        let svg = {
+            let mut ts = text_sphere.borrow_mut();
+            // A newer fetch_text has superseded this one
+            if ts.text_fetch_generation != generation {
+                return;
+            }
+            ts.text_loading = false;
+            if let Ok(text) = &result {
+                ts.set_text(text.trim());
+                ts.save_config();
+            }
+            ts.renderer.surface().clone()
+        };
+        // Listeners may call back into the API, so the borrow is released first
+        if let Err(err) = result {
+            dispatch_custom_event(&svg, "text-fetch-error", &err.detail_json(&url));
+        }
+    });
+}
+
+/// Ask to use the device's orientation sensor, then let it turn and tilt
+/// the sphere. iOS 13+ shows a permission prompt, which it only allows from
+/// a user gesture, so call this from e.g. a button's click handler. Other
+/// browsers need no permission. Resolves to whether the gyro was enabled.
+#[wasm_bindgen]
+pub fn request_gyro_permission(instance_id: u32) -> js_sys::Promise {
+    let Some(text_sphere) = text_sphere_handle(instance_id) else {
+        log::warn!("request_gyro_permission called before the sphere was initialized");
+        return js_sys::Promise::resolve(&JsValue::FALSE);
+    };
+
+    // The prompt must be requested synchronously, while the gesture lasts
+    let event_class = web_sys::window()
+        .and_then(|window| js_sys::Reflect::get(&window, &"DeviceOrientationEvent".into()).ok());
+    let request = event_class.and_then(|class| {
+        let request_permission = js_sys::Reflect::get(&class, &"requestPermission".into()).ok()?;
+        let request_permission: js_sys::Function = request_permission.dyn_into().ok()?;
+        Some(request_permission.call0(&class))
+    });
+
+    wasm_bindgen_futures::future_to_promise(async move {
+        let granted = match request {
+            None => true,
+            Some(Ok(promise)) => {
+                let state = JsFuture::from(js_sys::Promise::resolve(&promise)).await;
+                state.ok().and_then(|state| state.as_string()).as_deref() == Some("granted")
+            }
+            Some(Err(err)) => {
+                log::warn!("device orientation permission request failed: {:?}", err);
+                false
+            }
+        };
+        if granted {
+            let mut ts = text_sphere.borrow_mut();
+            ts.gyro_enabled = true;
+            ts.gyro_filtered = None;
+        }
+        Ok(JsValue::from_bool(granted))
+    })
+}
+
+/// Turn device-orientation control on or off. Where the browser needs
+/// permission (iOS), enable it with `request_gyro_permission` instead.
+#[wasm_bindgen]
+pub fn set_gyro_enabled(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| {
+        ts.gyro_enabled = enabled;
+        ts.gyro_filtered = None;
+    });
+}
+
+/// Stream the primary ring like a news ticker: characters enter behind the
+/// orbit, pass the front and slide off after three-quarters of a lap, with
+/// `push_ticker_text` (and `set_text`) queueing more
+#[wasm_bindgen]
+pub fn set_ticker_mode(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| ts.set_ticker_mode(enabled));
+}
+
+/// Queue text to follow the characters already in the ticker
+#[wasm_bindgen]
+pub fn push_ticker_text(instance_id: u32, text: &str) {
+    with_text_sphere(instance_id, |ts| ts.push_ticker_text(text));
+}
+
+/// Create an independent sphere in the element with id `container_id`.
+/// Returns the instance id that every other export takes.
+#[wasm_bindgen]
+pub fn create_sphere_instance(container_id: &str) -> Result<u32, JsValue> {
+    let text_sphere = TextSphere::new(container_id).ok_or_else(|| {
+        JsValue::from_str(&format!("could not create a sphere in #{}", container_id))
+    })?;
+    let text_sphere = Rc::new(RefCell::new(text_sphere));
+    let instance_id = NEXT_INSTANCE_ID.with(|next| {
+        let id = next.get();
+        next.set(id.wrapping_add(1));
+        id
+    });
+    TEXT_SPHERES.with(|spheres| {
+        spheres
+            .borrow_mut()
+            .insert(instance_id, text_sphere.clone())
+    });
+
+    setup_resize_handler(text_sphere.clone());
+    setup_mouse_handler(text_sphere.clone());
+    setup_touch_handler(text_sphere.clone());
+    setup_keyboard_handler(text_sphere.clone());
+    setup_device_orientation_handler(text_sphere.clone());
+    setup_visibility_handler(text_sphere.clone());
+    setup_focus_handler(text_sphere.clone());
+    setup_audio_handler(text_sphere.clone());
+    setup_drop_handler(text_sphere.clone());
+    setup_reduced_motion_handler(text_sphere.clone());
+    setup_high_contrast_handler(text_sphere.clone());
+    // Reduced motion leaves the sphere paused on a static frame
+    let paused = text_sphere.borrow().paused;
+    if !paused {
+        start_animation_loop(text_sphere);
+    }
+    Ok(instance_id)
+}
+
+/// Move the sphere into the element with id `id`, re-centering it on that
+/// element's size. Errors if there is no such element.
+#[wasm_bindgen]
+pub fn set_container_id(instance_id: u32, id: &str) -> Result<(), JsValue> {
+    let container = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.get_element_by_id(id))
+        .ok_or_else(|| JsValue::from_str(&format!("no element with id {:?}", id)))?;
+    with_text_sphere(instance_id, |ts| ts.move_to_container(container))
+        .unwrap_or_else(|| Err(JsValue::from_str("sphere is not initialized")))
+}
+
+/// Stop a sphere and remove it, its SVG and its event listeners from the page
+#[wasm_bindgen]
+pub fn destroy_sphere_instance(instance_id: u32) {
+    let removed = TEXT_SPHERES.with(|spheres| spheres.borrow_mut().remove(&instance_id));
+    match removed {
+        Some(text_sphere) => text_sphere.borrow_mut().destroy(),
+        None => log::warn!(
+            "destroy_sphere_instance: no sphere instance {}",
+            instance_id
+        ),
+    }
+}
+
+/// Capture the primary ring's animation state as a versioned object:
+/// `{version, current_angle, elapsed_ms, char_positions, orbit_radius,
+/// rotation_speed, center_x, center_y}`, where each position is
+/// `{char, screen_x, screen_y, z, opacity, scale_x, skew}`
+#[wasm_bindgen]
+pub fn snapshot(instance_id: u32) -> Result<js_sys::Object, JsValue> {
+    let snapshot = with_text_sphere(instance_id, |ts| ts.snapshot())
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+    let json = serde_json::to_string(&snapshot)
+        .map_err(|e| JsValue::from_str(&format!("could not serialize snapshot: {}", e)))?;
+    Ok(js_sys::JSON::parse(&json)?.unchecked_into())
+}
+
+/// Restore state captured by `snapshot` and redraw immediately
+#[wasm_bindgen]
+pub fn restore_snapshot(instance_id: u32, snapshot: &js_sys::Object) -> Result<(), JsValue> {
+    let json: String = js_sys::JSON::stringify(snapshot)?.into();
+    let snapshot: Snapshot = serde_json::from_str(&json)
+        .map_err(|e| JsValue::from_str(&format!("invalid snapshot: {}", e)))?;
+    with_text_sphere(instance_id, |ts| ts.restore_snapshot(&snapshot))
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Record the next `frames` animation frames. A `recording_complete` event
+/// with the array of frame snapshots fires when the last one is captured.
+#[wasm_bindgen]
+pub fn start_recording(instance_id: u32, frames: u32) {
+    with_text_sphere(instance_id, |ts| ts.start_recording(frames as usize));
+}
+
+/// Frames captured so far by `start_recording`: snapshot objects with an
+/// extra `delta_ms`, the time since the previous frame
+#[wasm_bindgen]
+pub fn get_recording(instance_id: u32) -> Result<js_sys::Array, JsValue> {
+    let json = with_text_sphere(instance_id, |ts| serde_json::to_string(&ts.recording))
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?
+        .map_err(|e| JsValue::from_str(&format!("could not serialize recording: {}", e)))?;
+    Ok(js_sys::JSON::parse(&json)?.unchecked_into())
+}
+
+/// Replay frames from `get_recording` at their original timing, pausing
+/// the live animation meanwhile. With `repeat` the playback loops until
+/// another one starts; otherwise the animation resumes at the end.
+/// An empty recording is rejected.
+#[wasm_bindgen]
+pub fn play_recording(
+    instance_id: u32,
+    frames: &js_sys::Array,
+    repeat: bool,
+) -> Result<(), JsValue> {
+    let json: String = js_sys::JSON::stringify(frames)?.into();
+    let frames: Vec<FrameSnapshot> = serde_json::from_str(&json)
+        .map_err(|e| JsValue::from_str(&format!("invalid recording: {}", e)))?;
+    if frames.is_empty() {
+        return Err(JsValue::from_str("recording has no frames"));
+    }
+    let text_sphere = text_sphere_handle(instance_id)
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?;
+
+    let playback = {
+        let mut ts = text_sphere.borrow_mut();
+        ts.playback_generation = ts.playback_generation.wrapping_add(1);
+        // Still set if this replaces a playback that paused the animation
+        if !ts.paused {
+            ts.paused = true;
+            ts.resume_after_playback = true;
+        }
+        Playback {
+            frames,
+            repeat,
+            generation: ts.playback_generation,
+        }
+    };
+    play_frame(text_sphere, Rc::new(playback), 0);
+    Ok(())
+}
+
+/// White-on-black rendering with fully opaque characters and no depth
+/// effects or glow. Turned on automatically under `prefers-contrast: more`;
+/// turning it off restores the previous colors.
+#[wasm_bindgen]
+pub fn set_high_contrast(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| ts.set_high_contrast(enabled));
+}
+
+/// Label screen readers give the sphere, replacing the default
+/// "Animated text sphere displaying: …" (an empty string restores it)
+#[wasm_bindgen]
+pub fn set_aria_label(instance_id: u32, label: &str) {
+    with_text_sphere(instance_id, |ts| {
+        ts.aria_label_override = (!label.is_empty()).then(|| label.to_string());
+        ts.update_accessible_text();
+    });
+}
+
+/// Whether to honor the OS `prefers-reduced-motion` setting (default true)
+/// by holding the sphere still. Hosts that never animate it anyway, or
+/// that ask the user themselves, can turn this off.
+#[wasm_bindgen]
+pub fn set_respect_reduced_motion(instance_id: u32, enabled: bool) {
+    if let Some(text_sphere) = text_sphere_handle(instance_id) {
+        let restart = {
+            let mut ts = text_sphere.borrow_mut();
+            ts.respect_reduced_motion = enabled;
+            ts.apply_reduced_motion(media_query_matches(REDUCED_MOTION_QUERY))
+        };
+        if restart {
+            start_animation_loop(text_sphere);
+        }
+    }
+}
+
+/// Defer SVG z-ordering and color updates to browser idle time instead of
+/// applying them every frame (default off). Positions, sizes and opacity
+/// still update each frame. Has no effect on the canvas and WebGL backends.
+#[wasm_bindgen]
+pub fn set_defer_updates(instance_id: u32, enabled: bool) {
+    with_text_sphere(instance_id, |ts| {
+        ts.defer_updates = enabled;
+        if !enabled {
+            ts.flush_deferred_updates();
+        }
+    });
+}
+
+/// Milliseconds without resize events before the sphere reflows to the new
+/// size (default 100; 0 reflows on every event)
+#[wasm_bindgen]
+pub fn set_resize_debounce_ms(instance_id: u32, ms: u32) {
+    with_text_sphere(instance_id, |ts| ts.resize_debounce_ms = ms);
+}
+
+/// Draw with `fillText` on a `<canvas>` instead of SVG elements, removing
+/// the SVG. Only offered where `OffscreenCanvas` exists, as a marker of a
+/// canvas implementation fast enough to be worth it. There is no way back.
+#[wasm_bindgen]
+pub fn use_canvas_backend(instance_id: u32) -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or_else(|| JsValue::from_str("no window"))?;
+    if !js_sys::Reflect::has(&window, &JsValue::from_str("OffscreenCanvas"))? {
+        return Err(JsValue::from_str(
+            "the canvas backend needs OffscreenCanvas support",
+        ));
+    }
+    with_text_sphere(instance_id, |ts| ts.use_canvas_backend())
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?
+}
+
+/// Draw with WebGL into the page's `<canvas id="canvas_id">`, sized to the
+/// sphere's container, replacing the current backend (the SVG is removed).
+/// Characters are textured quads from a glyph atlas, drawn in one call per
+/// frame; stroke outlines and glow are not supported.
+#[wasm_bindgen]
+pub fn init_webgl_backend(instance_id: u32, canvas_id: &str) -> Result<(), JsValue> {
+    let canvas: web_sys::HtmlCanvasElement = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(canvas_id))
+        .and_then(|e| e.dyn_into().ok())
+        .ok_or_else(|| JsValue::from_str(&format!("no <canvas> with id {}", canvas_id)))?;
+    let renderer = WebGlRenderer::new(canvas)?;
+    with_text_sphere(instance_id, |ts| ts.replace_renderer(Box::new(renderer)))
+        .ok_or_else(|| JsValue::from_str("sphere is not initialized"))?
+}
+
+// ============================================================================
+// Entry Point
+// ============================================================================
+
+// Not the start function in wasm test builds, whose harness defines `main`
+#[cfg_attr(not(test), wasm_bindgen(start))]
+pub fn main() {
+    wasm_logger::init(wasm_logger::Config::default());
+    log::info!("d3-text-sphere starting...");
+
+    match create_sphere_instance(DEFAULT_CONTAINER_ID) {
+        Ok(instance_id) => log::info!("d3-text-sphere running as instance {}", instance_id),
+        Err(_) => log::error!("Failed to initialize TextSphere"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(id: i32, x: f64, y: f64) -> TouchPoint {
+        TouchPoint { id, x, y }
+    }
+
+    #[test]
+    fn single_touch_starts_and_continues_a_drag() {
+        assert_eq!(
+            begin_gesture(&[touch(7, 120.0, 40.0)]),
+            TouchGesture::Drag { x: 120.0 }
+        );
+        assert_eq!(
+            move_gesture(&[touch(7, 90.0, 45.0)], None),
+            TouchGesture::Drag { x: 90.0 }
+        );
+    }
+
+    #[test]
+    fn dual_touch_starts_a_pinch() {
+        assert_eq!(
+            begin_gesture(&[touch(1, 0.0, 0.0), touch(2, 30.0, 40.0)]),
+            TouchGesture::Pinch {
+                ids: (1, 2),
+                distance: 50.0
+            }
+        );
+    }
+
+    #[test]
+    fn pinch_tracks_its_fingers_by_identifier() {
+        let points = [
+            touch(3, 0.0, 0.0),
+            touch(2, 60.0, 80.0),
+            touch(1, 0.0, 20.0),
+        ];
+        assert_eq!(
+            move_gesture(&points, Some((1, 2))),
+            TouchGesture::Pinch {
+                ids: (1, 2),
+                distance: 60.0_f64.hypot(60.0)
+            }
+        );
+        // A lost finger pauses the pinch rather than turning it into a drag
+        assert_eq!(
+            move_gesture(&[touch(1, 0.0, 0.0)], Some((1, 2))),
+            TouchGesture::None
+        );
+    }
+
+    #[test]
+    fn lifting_every_finger_ends_the_gesture() {
+        assert_eq!(begin_gesture(&[]), TouchGesture::None);
+        assert_eq!(move_gesture(&[], None), TouchGesture::None);
+    }
+
+    #[test]
+    fn short_randomize_springs_settle() {
+        // The stiffness randomize_positions picks for a 10 ms duration
+        let omega = 12.0 / 0.01;
+        let mut spring = SpringToBase {
+            target_angle: 0.0,
+            spring_k: omega * omega,
+            vel: 0.0,
+        };
+        let mut offset = PI;
+        for _ in 0..60 {
+            offset = spring.step(offset, 1.0 / 60.0);
+        }
+        assert!(offset.abs() < 1e-4, "offset {} after a second", offset);
+    }
+
+    #[test]
+    fn pinch_radius_is_clamped() {
+        assert_eq!(pinched_radius(200.0, 25.0), 225.0);
+        assert_eq!(pinched_radius(60.0, -40.0), PINCH_MIN_RADIUS);
+        assert_eq!(pinched_radius(480.0, 100.0), PINCH_MAX_RADIUS);
+    }
+
+    #[test]
+    fn trig_table_matches_sin_cos_for_any_angle() {
+        let table = TrigTable::new();
+        for i in -400..400 {
+            let angle = i as f64 * 0.0371;
+            let (sin, cos) = table.sin_cos(angle);
+            assert!((sin - angle.sin()).abs() < 1e-3, "sin({})", angle);
+            assert!((cos - angle.cos()).abs() < 1e-3, "cos({})", angle);
+        }
+    }
+
+    #[test]
+    fn hash_config_reads_every_key() {
+        let config = parse_hash_config(
+            "#text=Hello%20World&speed=0.5&orbit_radius=120&inclination=30\
+             &perspective=800&font=Courier+New&bg_color=%23102030",
+        );
+        assert_eq!(config.text.as_deref(), Some("Hello World"));
+        assert_eq!(config.rotation_speed, Some(0.5));
+        assert_eq!(config.orbit_radius, Some(120.0));
+        assert_eq!(config.inclination, Some(30.0));
+        assert_eq!(config.perspective_distance, Some(800.0));
+        assert_eq!(config.font_family.as_deref(), Some("Courier New"));
+        assert_eq!(config.background_color.as_deref(), Some("#102030"));
+    }
+
+    #[test]
+    fn hash_config_skips_bad_values_and_unknown_keys() {
+        let config = parse_hash_config("speed=fast&&colour=red&orbit_radius=90&text=100%");
+        assert_eq!(config.rotation_speed, None);
+        assert_eq!(config.orbit_radius, Some(90.0));
+        // A malformed escape is kept as written
+        assert_eq!(config.text.as_deref(), Some("100%"));
+
+        let empty = parse_hash_config("");
+        assert!(empty.text.is_none() && empty.rotation_speed.is_none());
+    }
+
+    #[test]
+    fn hash_config_round_trips() {
+        let config = SphereConfig {
+            text: Some("a&b=c ü".to_string()),
+            rotation_speed: Some(-1.25),
+            font_family: Some("Georgia, serif".to_string()),
+            ..SphereConfig::default()
+        };
+        let parsed = parse_hash_config(&build_hash_config(&config));
+        assert_eq!(parsed.text, config.text);
+        assert_eq!(parsed.rotation_speed, config.rotation_speed);
+        assert_eq!(parsed.font_family, config.font_family);
+        assert_eq!(parsed.orbit_radius, None);
+    }
+
+    /// Values a setter could leave behind that `validate` rejects
+    fn out_of_range_config(bad: f64) -> SphereConfig {
+        SphereConfig {
+            text: Some("hello".to_string()),
+            rotation_speed: Some(bad * 100.0),
+            orbit_radius: Some(bad * 1500.0),
+            letter_size: Some(bad * 2000.0),
+            letter_spacing: Some(bad * 12.0),
+            start_angle: Some(bad * 720.0),
+            sphere_radius: Some(bad * 1500.0),
+            perspective_distance: Some(-bad),
+            skew_intensity: Some(bad * 2.0),
+            inclination: Some(bad * 180.0),
+            min_opacity: Some(bad * 2.0),
+            depth_color_blend: Some(bad * 2.0),
+            font_weight: Some("heavy".to_string()),
+            background_color: Some("#nothex".to_string()),
+            color_scheme: Some("plaid".to_string()),
+            ..SphereConfig::default()
+        }
+    }
+
+    #[test]
+    fn clamped_config_always_passes_validate() {
+        // config() returns clamped(), so its output always loads back
+        for bad in [1.0, -1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let config = out_of_range_config(bad);
+            if bad.is_finite() {
+                assert!(config.validate().is_err());
+            }
+            let config = config.clamped();
+            assert_eq!(config.validate(), Ok(()), "from {}", bad);
+            let json = serde_json::to_string(&config).unwrap();
+            assert!(parse_config(&json).is_ok(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn clamped_config_keeps_valid_values() {
+        let config = SphereConfig {
+            rotation_speed: Some(-1.5),
+            letter_spacing: Some(0.8),
+            sphere_radius: Some(120.0),
+            font_weight: Some("bold".to_string()),
+            color_scheme: Some("rainbow".to_string()),
+            ..SphereConfig::default()
+        }
+        .clamped();
+        assert_eq!(config.rotation_speed, Some(-1.5));
+        assert_eq!(config.letter_spacing, Some(0.8));
+        assert_eq!(config.sphere_radius, Some(120.0));
+        assert_eq!(config.font_weight.as_deref(), Some("bold"));
+        assert_eq!(config.color_scheme.as_deref(), Some("rainbow"));
+    }
+
+    #[test]
+    fn saved_config_with_a_bad_value_keeps_the_others() {
+        let json = r#"{"text": "hi", "letter_spacing": 12, "rotation_speed": 0.5}"#;
+        assert!(parse_config(json).is_err());
+        let config = parse_saved_config(json).unwrap();
+        assert_eq!(config.text.as_deref(), Some("hi"));
+        assert_eq!(config.rotation_speed, Some(0.5));
+        assert_eq!(config.letter_spacing, Some(MAX_LETTER_SPACING));
+    }
+
+    #[test]
+    fn wide_characters_take_twice_the_orbit() {
+        let clusters = grapheme_clusters("ABC中文DE");
+        let widths: Vec<f64> = clusters.iter().map(|c| width_factor(c)).collect();
+        assert_eq!(clusters, ["A", "B", "C", "中", "文", "D", "E"]);
+        assert_eq!(widths, [1.0, 1.0, 1.0, 2.0, 2.0, 1.0, 1.0]);
+
+        // Each character's arc is its width over the total of 9, so the
+        // step between neighbours is the mean of their two arcs
+        let angles = OrbitRing::base_angles(&widths, 1.0, PI / 2.0);
+        let unit = TAU / 9.0;
+        for i in 0..angles.len() - 1 {
+            let step = angles[i] - angles[i + 1];
+            let expected = (widths[i] + widths[i + 1]) / 2.0 * unit;
+            assert!(
+                (step - expected).abs() < 1e-9,
+                "step {} after {}",
+                step,
+                clusters[i]
+            );
+        }
+        // The text stays centered on the start angle
+        let middle = (angles[0] + unit / 2.0 + angles[6] - unit / 2.0) / 2.0;
+        assert!((middle - PI / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn single_character_sits_at_the_start_angle() {
+        assert_eq!(OrbitRing::base_angles(&[1.0], 1.0, PI / 2.0), [PI / 2.0]);
+        assert_eq!(OrbitRing::base_angles(&[2.0], 0.5, 1.25), [1.25]);
+    }
+
+    #[test]
+    fn wrap_text_breaks_between_words() {
+        assert_eq!(
+            wrap_text("the quick brown fox jumps", 10),
+            ["the quick", "brown fox", "jumps"]
+        );
+        // A word that exactly fills a line isn't split
+        assert_eq!(wrap_text("orbit sphere", 6), ["orbit", "sphere"]);
+    }
+
+    #[test]
+    fn wrap_text_hyphenates_words_longer_than_a_line() {
+        assert_eq!(wrap_text("abcdefghij", 4), ["abc-", "def-", "ghij"]);
+        assert_eq!(wrap_text("a abcdefg b", 4), ["a", "abc-", "defg", "b"]);
+    }
+
+    #[test]
+    fn wrap_text_with_room_for_one_character_skips_hyphens() {
+        for max in [0, 1] {
+            assert_eq!(wrap_text("ab c", max), ["a", "b", "c"], "max {}", max);
+        }
+    }
+
+    #[test]
+    fn wrap_text_of_whitespace_is_empty() {
+        assert!(wrap_text("", 10).is_empty());
+        assert!(wrap_text(" \t\n  ", 10).is_empty());
+    }
+
+    #[test]
+    fn wrap_text_counts_grapheme_clusters() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let e_acute = "e\u{301}";
+        // Each joined emoji or combining sequence is one character
+        assert_eq!(
+            wrap_text(&format!("{0}{0} 中文", family), 3),
+            [family.repeat(2), "中文".to_string()]
+        );
+        // and a long word is never split inside a cluster
+        assert_eq!(
+            wrap_text(&e_acute.repeat(3), 2),
+            [format!("{}-", e_acute), e_acute.repeat(2)]
+        );
+    }
+
+    /// Tests that need a JS engine, run with `wasm-pack test`
+    #[cfg(target_arch = "wasm32")]
+    mod wasm {
+        use super::*;
+        use wasm_bindgen_test::wasm_bindgen_test;
+
+        wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen]
+        extern "C" {
+            #[wasm_bindgen(js_namespace = performance)]
+            fn now() -> f64;
+        }
+
+        /// Sum of the orbit points `animate` projects for `chars` evenly spaced
+        /// characters over `frames` frames, using `sin_cos` for the trig
+        fn orbit_sum(frames: usize, chars: usize, sin_cos: impl Fn(f64) -> (f64, f64)) -> f64 {
+            let mut sum = 0.0;
+            for frame in 0..frames {
+                let current_angle = frame as f64 * 0.016;
+                for i in 0..chars {
+                    let angle = current_angle + i as f64 / chars as f64 * TAU;
+                    let (sin, cos) = sin_cos(std::hint::black_box(angle));
+                    sum += ORBIT_RADIUS * cos + ORBIT_RADIUS * sin;
+                }
+            }
+            sum
+        }
+
+        /// Milliseconds `f` takes, by `performance.now()`
+        fn time_ms(f: impl FnOnce() -> f64) -> f64 {
+            let start = now();
+            std::hint::black_box(f());
+            now() - start
+        }
+
+        #[wasm_bindgen_test]
+        fn trig_table_cuts_orbit_trig_time() {
+            const FRAMES: usize = 2000;
+            const CHARS: usize = 64;
+            let table = TrigTable::new();
+            // Warm up both paths before measuring
+            orbit_sum(10, CHARS, f64::sin_cos);
+            orbit_sum(10, CHARS, |angle| table.sin_cos(angle));
+
+            let direct = time_ms(|| orbit_sum(FRAMES, CHARS, f64::sin_cos));
+            let lookup = time_ms(|| orbit_sum(FRAMES, CHARS, |angle| table.sin_cos(angle)));
+            log::info!(
+                "orbit trig: {:.1} ms direct, {:.1} ms table",
+                direct,
+                lookup
+            );
+            assert!(
+                lookup <= direct * 0.8,
+                "table lookup took {:.1} ms against {:.1} ms for sin_cos",
+                lookup,
+                direct
+            );
+        }
+
+        /// Load d3 from the CDN `index.html` uses, once per test page
+        async fn load_d3(document: &web_sys::Document) -> Result<(), JsValue> {
+            if js_sys::Reflect::has(&js_sys::global(), &JsValue::from_str("d3"))? {
+                return Ok(());
+            }
+            let script = document.create_element("script")?;
+            script.set_attribute("src", "https://d3js.org/d3.v7.min.js")?;
+            let loaded = js_sys::Promise::new(&mut |resolve, reject| {
+                let _ = script.add_event_listener_with_callback("load", &resolve);
+                let _ = script.add_event_listener_with_callback("error", &reject);
+            });
+            document
+                .body()
+                .ok_or_else(|| JsValue::from_str("no body"))?
+                .append_child(&script)?;
+            wasm_bindgen_futures::JsFuture::from(loaded).await?;
+            Ok(())
+        }
+
+        #[wasm_bindgen_test]
+        async fn snapshot_restore_round_trips() {
+            let document = web_sys::window().unwrap().document().unwrap();
+            load_d3(&document).await.expect("d3 loads");
+            let container = document.create_element("div").unwrap();
+            container.set_id("snapshot-round-trip");
+            container
+                .set_attribute("style", "width: 400px; height: 300px")
+                .unwrap();
+            document.body().unwrap().append_child(&container).unwrap();
+
+            let id = create_sphere_instance("snapshot-round-trip").expect("sphere is created");
+            pause(id);
+            seek(id, 1.0);
+            let before = snapshot(id).unwrap();
+            seek(id, 2.5);
+            let moved = snapshot(id).unwrap();
+            restore_snapshot(id, &before).unwrap();
+            let after = snapshot(id).unwrap();
+            destroy_sphere_instance(id);
+
+            let json =
+                |object: &js_sys::Object| String::from(js_sys::JSON::stringify(object).unwrap());
+            assert_ne!(json(&before), json(&moved));
+            assert_eq!(json(&before), json(&after));
+        }
     }
 }